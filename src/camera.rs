@@ -0,0 +1,83 @@
+//! View transform.
+//!
+//! The camera maps curve space — the space keys live in — to view space, where the visible area
+//! spans [-1, 1] vertically and [-aspect, aspect] horizontally (see [`crate::coords`]). Rendering
+//! applies that mapping in the shaders; picking applies its inverse to the cursor.
+
+use cgmath::{ElementWise as _, Vector2};
+
+use crate::model::ScreenPos;
+
+/// Zoom factor applied for each scroll step.
+const ZOOM_STEP: f32 = 1.1;
+/// Fraction of the framed area added around it when framing.
+const FRAME_MARGIN: f32 = 0.1;
+/// Extent used on an axis when framing something flat on that axis.
+const DEFAULT_EXTENT: f32 = 1.;
+/// Smallest scale zooming out can reach, per axis.
+pub const MIN_SCALE: f32 = 1e-3;
+/// Largest scale zooming in can reach, per axis.
+pub const MAX_SCALE: f32 = 1e4;
+
+/// 2D camera with pan and zoom.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Camera {
+  /// Curve-space position at the center of the view.
+  pub center: ScreenPos,
+  /// Scale from curve space to view space, per axis.
+  pub scale: Vector2<f32>,
+}
+
+impl Default for Camera {
+  /// Show [0, 1] on the X axis and [-1, 1] on the Y axis.
+  fn default() -> Self {
+    Camera {
+      center: ScreenPos::new(0.5, 0.),
+      scale: Vector2::new(2., 1.),
+    }
+  }
+}
+
+impl Camera {
+  /// Map a curve-space position to view space.
+  pub fn to_view(&self, p: ScreenPos) -> ScreenPos {
+    (p - self.center).mul_element_wise(self.scale)
+  }
+
+  /// Map a view-space position to curve space.
+  pub fn from_view(&self, p: ScreenPos) -> ScreenPos {
+    p.div_element_wise(self.scale) + self.center
+  }
+
+  /// Move the camera so that the content follows a displacement expressed in view space.
+  pub fn pan(&mut self, view_delta: ScreenPos) {
+    self.center -= view_delta.div_element_wise(self.scale);
+  }
+
+  /// Zoom by a number of scroll steps around an anchor expressed in view space.
+  ///
+  /// The curve-space position under the anchor stays under the anchor. Positive steps zoom in.
+  /// The scale is clamped between [`MIN_SCALE`] and [`MAX_SCALE`] on each axis.
+  pub fn zoom(&mut self, steps: f32, view_anchor: ScreenPos) {
+    let anchor = self.from_view(view_anchor);
+
+    let factor = ZOOM_STEP.powf(steps);
+    self.scale = self
+      .scale
+      .map(|scale| (scale * factor).clamp(MIN_SCALE, MAX_SCALE));
+    self.center = anchor - view_anchor.div_element_wise(self.scale);
+  }
+
+  /// Make the camera show the curve-space area between `min` and `max`, with a small margin, in a
+  /// view of the given aspect ratio. As with [`Camera::zoom`], the scale is clamped.
+  pub fn frame(&mut self, min: ScreenPos, max: ScreenPos, aspect_ratio: f32) {
+    let extent = max - min;
+    let fit = |e: f32, half_size: f32| {
+      let e = if e > f32::EPSILON { e } else { DEFAULT_EXTENT };
+      (2. * half_size / (e * (1. + 2. * FRAME_MARGIN))).clamp(MIN_SCALE, MAX_SCALE)
+    };
+
+    self.center = (min + max) * 0.5;
+    self.scale = Vector2::new(fit(extent.x, aspect_ratio), fit(extent.y, 1.));
+  }
+}
@@ -0,0 +1,129 @@
+//! Coordinate spaces.
+//!
+//! Positions go through several spaces between the cursor and the curve:
+//!
+//! - _Window space_: screen coordinates as reported by the windowing system for cursor events,
+//!   origin at the top-left corner, Y pointing down. On HiDPI displays, a unit in that space might
+//!   span several pixels.
+//! - _Framebuffer space_: pixels of the framebuffer, origin at the top-left corner, Y pointing
+//!   down.
+//! - _NDC_: normalized device coordinates, spanning [-1, 1] on both axes, Y pointing up.
+//! - _View space_: NDC corrected by the aspect ratio, so that a unit has the same length on both
+//!   axes; Y spans [-1, 1] and X spans [-aspect, aspect].
+//! - _Curve space_: the space keys live in. It’s mapped to view space by a [`Camera`].
+//!
+//! [`Viewport`] handles everything from window space to view space; [`Camera`] does the rest.
+
+use cgmath::{ElementWise as _, Vector2};
+
+use crate::{camera::Camera, model::ScreenPos};
+
+/// Dimensions of the area the editor is displayed in.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Viewport {
+  // Size of the window, in screen coordinates.
+  window_size: Vector2<f32>,
+  // Size of the framebuffer, in pixels.
+  framebuffer_size: Vector2<f32>,
+}
+
+impl Viewport {
+  /// Create a viewport from the window size (screen coordinates) and framebuffer size (pixels).
+  pub fn new(window_size: [u32; 2], framebuffer_size: [u32; 2]) -> Self {
+    let mut viewport = Viewport {
+      window_size: Vector2::new(1., 1.),
+      framebuffer_size: Vector2::new(1., 1.),
+    };
+
+    viewport.resize_window(window_size);
+    viewport.resize_framebuffer(framebuffer_size);
+    viewport
+  }
+
+  /// Update the size of the window, in screen coordinates.
+  pub fn resize_window(&mut self, [w, h]: [u32; 2]) {
+    // minimized windows have a null size; keep the viewport usable
+    self.window_size = Vector2::new(w.max(1) as f32, h.max(1) as f32);
+  }
+
+  /// Update the size of the framebuffer, in pixels.
+  pub fn resize_framebuffer(&mut self, [w, h]: [u32; 2]) {
+    self.framebuffer_size = Vector2::new(w.max(1) as f32, h.max(1) as f32);
+  }
+
+  /// Size of the framebuffer, in pixels.
+  pub fn framebuffer_size(&self) -> Vector2<f32> {
+    self.framebuffer_size
+  }
+
+  /// Number of framebuffer pixels per window unit, per axis.
+  pub fn content_scale(&self) -> Vector2<f32> {
+    self.framebuffer_size.div_element_wise(self.window_size)
+  }
+
+  /// Width divided by height.
+  pub fn aspect_ratio(&self) -> f32 {
+    self.framebuffer_size.x / self.framebuffer_size.y
+  }
+
+  /// Length of a window unit in view space.
+  pub fn pixel_size(&self) -> f32 {
+    2. / self.window_size.y
+  }
+
+  /// Map a window-space position to framebuffer space.
+  pub fn window_to_framebuffer(&self, p: ScreenPos) -> ScreenPos {
+    p.mul_element_wise(self.content_scale())
+  }
+
+  /// Map a framebuffer-space position to window space.
+  pub fn framebuffer_to_window(&self, p: ScreenPos) -> ScreenPos {
+    p.div_element_wise(self.content_scale())
+  }
+
+  /// Map a framebuffer-space position to NDC.
+  pub fn framebuffer_to_ndc(&self, p: ScreenPos) -> ScreenPos {
+    ScreenPos::new(
+      2. * p.x / self.framebuffer_size.x - 1.,
+      1. - 2. * p.y / self.framebuffer_size.y,
+    )
+  }
+
+  /// Map a NDC position to framebuffer space.
+  pub fn ndc_to_framebuffer(&self, p: ScreenPos) -> ScreenPos {
+    ScreenPos::new(
+      (p.x + 1.) * 0.5 * self.framebuffer_size.x,
+      (1. - p.y) * 0.5 * self.framebuffer_size.y,
+    )
+  }
+
+  /// Map a NDC position to view space.
+  pub fn ndc_to_view(&self, p: ScreenPos) -> ScreenPos {
+    ScreenPos::new(p.x * self.aspect_ratio(), p.y)
+  }
+
+  /// Map a view-space position to NDC.
+  pub fn view_to_ndc(&self, p: ScreenPos) -> ScreenPos {
+    ScreenPos::new(p.x / self.aspect_ratio(), p.y)
+  }
+
+  /// Map a window-space position to view space.
+  pub fn window_to_view(&self, p: ScreenPos) -> ScreenPos {
+    self.ndc_to_view(self.framebuffer_to_ndc(self.window_to_framebuffer(p)))
+  }
+
+  /// Map a view-space position to window space.
+  pub fn view_to_window(&self, p: ScreenPos) -> ScreenPos {
+    self.framebuffer_to_window(self.ndc_to_framebuffer(self.view_to_ndc(p)))
+  }
+
+  /// Map a window-space position to curve space.
+  pub fn window_to_curve(&self, camera: &Camera, p: ScreenPos) -> ScreenPos {
+    camera.from_view(self.window_to_view(p))
+  }
+
+  /// Map a curve-space position to window space.
+  pub fn curve_to_window(&self, camera: &Camera, p: ScreenPos) -> ScreenPos {
+    self.view_to_window(camera.to_view(p))
+  }
+}
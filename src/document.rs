@@ -0,0 +1,142 @@
+//! On-disk representation of the edited channels.
+//!
+//! A document is a versioned JSON file. The version is bumped whenever the layout changes: documents
+//! written with a newer version are rejected when loading them, and older ones are migrated.
+//! Documents written before channels existed hold a single spline, which is loaded as the only
+//! channel.
+
+use serde::{Deserialize, Serialize};
+use splines::Spline;
+use std::{
+  fmt,
+  fs::File,
+  io::{self, BufReader, BufWriter},
+  path::Path,
+};
+
+use crate::{
+  mode::CurveMode,
+  model::{ScreenPos, CHANNEL_COLORS},
+  tangent::TangentMode,
+};
+
+/// Current version of the document format.
+pub const DOCUMENT_VERSION: u32 = 6;
+
+/// Name of the channel holding the spline of documents written before channels existed.
+pub const LEGACY_CHANNEL_NAME: &str = "curve";
+
+/// A document, holding everything required to restore an editing session.
+#[derive(Debug, Deserialize, Serialize)]
+pub struct Document {
+  /// Version of the format used to write the document.
+  pub version: u32,
+  /// How times relate to values; older documents are [`CurveMode::Function`] ones. Added in
+  /// version 4, [`CurveMode::Gradient`] in version 5 and [`CurveMode::Space`] in version 6.
+  #[serde(default)]
+  pub mode: CurveMode,
+  /// The edited channels, in order. Added in version 3.
+  #[serde(default)]
+  pub channels: Vec<ChannelDocument>,
+  // Single spline of documents older than version 3, moved into a channel once loaded.
+  #[serde(default, skip_serializing)]
+  spline: Option<Spline<f32, ScreenPos>>,
+  // Tangent modes going with `spline`.
+  #[serde(default, skip_serializing)]
+  tangents: Vec<TangentMode>,
+}
+
+/// A channel of a document.
+#[derive(Debug, Deserialize, Serialize)]
+pub struct ChannelDocument {
+  /// Name of the channel.
+  pub name: String,
+  /// Color the curve is drawn with.
+  pub color: [f32; 3],
+  /// Whether the curve is drawn.
+  pub visible: bool,
+  /// Whether the curve is protected against edits.
+  pub locked: bool,
+  /// The edited spline.
+  pub spline: Spline<f32, ScreenPos>,
+  /// Tangent mode of each key; missing ones are [`TangentMode::Free`].
+  #[serde(default)]
+  pub tangents: Vec<TangentMode>,
+}
+
+impl Document {
+  /// Create a document out of channels, using the current version.
+  pub fn new(mode: CurveMode, channels: Vec<ChannelDocument>) -> Self {
+    Document {
+      version: DOCUMENT_VERSION,
+      mode,
+      channels,
+      spline: None,
+      tangents: Vec::new(),
+    }
+  }
+
+  /// Channel with the given name, if any.
+  pub fn channel(&self, name: &str) -> Option<&ChannelDocument> {
+    self.channels.iter().find(|channel| channel.name == name)
+  }
+
+  /// Load a document from a file.
+  pub fn load(path: impl AsRef<Path>) -> Result<Self, DocumentError> {
+    let file = File::open(path).map_err(DocumentError::IOError)?;
+    let mut doc: Self =
+      serde_json::from_reader(BufReader::new(file)).map_err(DocumentError::FormatError)?;
+
+    if doc.version > DOCUMENT_VERSION {
+      return Err(DocumentError::UnsupportedVersion(doc.version));
+    }
+
+    // older documents hold a single spline
+    if let Some(spline) = doc.spline.take() {
+      doc.channels.insert(
+        0,
+        ChannelDocument {
+          name: LEGACY_CHANNEL_NAME.to_owned(),
+          color: CHANNEL_COLORS[0],
+          visible: true,
+          locked: false,
+          spline,
+          tangents: std::mem::take(&mut doc.tangents),
+        },
+      );
+    }
+
+    Ok(doc)
+  }
+
+  /// Save a document into a file, overwriting it if it already exists.
+  pub fn save(&self, path: impl AsRef<Path>) -> Result<(), DocumentError> {
+    let file = File::create(path).map_err(DocumentError::IOError)?;
+    serde_json::to_writer_pretty(BufWriter::new(file), self).map_err(DocumentError::FormatError)
+  }
+}
+
+/// Possible errors that might occur while loading or saving a document.
+#[derive(Debug)]
+pub enum DocumentError {
+  /// The file couldn’t be read or written.
+  IOError(io::Error),
+  /// The content of the file is not a valid document.
+  FormatError(serde_json::Error),
+  /// The document was written with a newer, unsupported version of the format.
+  UnsupportedVersion(u32),
+}
+
+impl fmt::Display for DocumentError {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    match *self {
+      DocumentError::IOError(ref e) => write!(f, "I/O error: {}", e),
+      DocumentError::FormatError(ref e) => write!(f, "malformed document: {}", e),
+      DocumentError::UnsupportedVersion(v) => write!(
+        f,
+        "unsupported document version {} (latest supported: {})",
+        v, DOCUMENT_VERSION
+      ),
+    }
+  }
+}
@@ -0,0 +1,409 @@
+//! Export of scalar splines, baked sample tables and color ramps.
+//!
+//! Runtimes embedding the `splines` crate can load exported splines directly: they are written as
+//! the JSON form of `Spline<f32, f32>`, function curves being converted with
+//! [`to_scalar`](crate::scalar::to_scalar); 3D paths are written as `Spline<f32, Vector3<f32>>`,
+//! see [`SpaceCurve::to_spline`]. Other runtimes can use a [`Table`] instead: the curve
+//! sampled at a fixed rate between its first and last keys. A table can be written as CSV, as a
+//! binary blob or as Rust / GLSL source code.
+//!
+//! The binary format is little-endian and made of a 20-byte header followed by the samples:
+//!
+//! | Offset | Type       | Content                  |
+//! |--------|------------|--------------------------|
+//! | 0      | `[u8; 4]`  | [`BINARY_MAGIC`]         |
+//! | 4      | `u32`      | [`BINARY_VERSION`]       |
+//! | 8      | `u32`      | number of samples        |
+//! | 12     | `f32`      | time of the first sample |
+//! | 16     | `f32`      | time of the last sample  |
+//! | 20     | `[f32; n]` | samples                  |
+//!
+//! Gradients are exported as [`Ramp`]s: one-dimensional RGBA8 textures, written in a similar
+//! format, with [`RAMP_EXTENSION`] files:
+//!
+//! | Offset | Type         | Content                 |
+//! |--------|--------------|-------------------------|
+//! | 0      | `[u8; 4]`    | [`RAMP_MAGIC`]          |
+//! | 4      | `u32`        | [`RAMP_VERSION`]        |
+//! | 8      | `u32`        | width, in texels        |
+//! | 12     | `f32`        | time of the first texel |
+//! | 16     | `f32`        | time of the last texel  |
+//! | 20     | `[u8; 4 n]`  | RGBA texels             |
+
+use splines::Spline;
+use std::{
+  fmt,
+  fs::File,
+  io::{self, BufWriter, Write},
+  path::Path,
+};
+
+use crate::{
+  gradient::{self, Gradient},
+  model::ScreenPos,
+  scalar,
+  space::SpaceCurve,
+};
+
+/// Magic bytes starting binary tables.
+pub const BINARY_MAGIC: [u8; 4] = *b"SPLT";
+/// Current version of the binary format.
+pub const BINARY_VERSION: u32 = 1;
+/// Extension of exported splines.
+pub const SPLINE_EXTENSION: &str = "json";
+/// Magic bytes starting ramp textures.
+pub const RAMP_MAGIC: [u8; 4] = *b"SPLR";
+/// Current version of the ramp format.
+pub const RAMP_VERSION: u32 = 1;
+/// Extension of ramp textures.
+pub const RAMP_EXTENSION: &str = "ramp";
+
+/// Maximum number of samples of a table or a ramp.
+pub const MAX_SAMPLES: usize = 1 << 20;
+
+/// How a curve is sampled, with at most [`MAX_SAMPLES`] samples.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Sampling {
+  /// Number of samples per time unit.
+  Rate(f32),
+  /// Total number of samples, the first and last ones lying on the first and last keys.
+  Count(usize),
+}
+
+/// Format of an exported table.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ExportFormat {
+  /// One `t,value` line per sample, after a header line.
+  Csv,
+  /// Little-endian binary blob; see the [module documentation](self).
+  Binary,
+  /// Rust `const` array.
+  Rust,
+  /// GLSL `const float[]` array.
+  Glsl,
+}
+
+impl ExportFormat {
+  /// All the formats.
+  pub const ALL: [ExportFormat; 4] = [
+    ExportFormat::Csv,
+    ExportFormat::Binary,
+    ExportFormat::Rust,
+    ExportFormat::Glsl,
+  ];
+
+  /// Guess a format from a file extension.
+  pub fn from_extension(ext: &str) -> Option<Self> {
+    match ext {
+      "csv" => Some(ExportFormat::Csv),
+      "bin" => Some(ExportFormat::Binary),
+      "rs" => Some(ExportFormat::Rust),
+      "glsl" => Some(ExportFormat::Glsl),
+      _ => None,
+    }
+  }
+
+  /// Usual file extension of the format.
+  pub fn extension(self) -> &'static str {
+    match self {
+      ExportFormat::Csv => "csv",
+      ExportFormat::Binary => "bin",
+      ExportFormat::Rust => "rs",
+      ExportFormat::Glsl => "glsl",
+    }
+  }
+}
+
+/// A curve sampled at a fixed rate.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Table {
+  /// Time of the first sample.
+  pub start: f32,
+  /// Time of the last sample.
+  pub end: f32,
+  /// Sampled values, regularly spaced in time.
+  pub values: Vec<f32>,
+}
+
+impl Table {
+  /// Sample the value of a function curve — the vertical coordinate of its keys — between its first
+  /// and last keys.
+  pub fn bake(spline: &Spline<f32, ScreenPos>, sampling: Sampling) -> Result<Self, ExportError> {
+    let keys = spline.keys();
+    let (start, end) = match (keys.first(), keys.last()) {
+      (Some(first), Some(last)) => (first.t, last.t),
+      _ => return Err(ExportError::EmptySpline),
+    };
+
+    let (count, step) = sample_count(start, end, sampling)?;
+
+    let values = (0..count)
+      .map(|i| {
+        let t = start + step * i as f32;
+        spline
+          .clamped_sample(t)
+          .map(|p| p.y)
+          .ok_or(ExportError::CannotSample(t))
+      })
+      .collect::<Result<_, _>>()?;
+    let end = start + step * (count - 1) as f32;
+
+    Ok(Table { start, end, values })
+  }
+
+  /// Time between two samples.
+  pub fn step(&self) -> f32 {
+    if self.values.len() < 2 {
+      0.
+    } else {
+      (self.end - self.start) / (self.values.len() - 1) as f32
+    }
+  }
+
+  /// Time of a sample.
+  fn time(&self, i: usize) -> f32 {
+    self.start + self.step() * i as f32
+  }
+
+  /// Write the table in a given format.
+  ///
+  /// `name` is the name of the generated constants for source formats; it is ignored otherwise.
+  pub fn write(&self, mut w: impl Write, format: ExportFormat, name: &str) -> io::Result<()> {
+    match format {
+      ExportFormat::Csv => {
+        writeln!(w, "t,value")?;
+
+        for (i, x) in self.values.iter().enumerate() {
+          writeln!(w, "{},{}", self.time(i), x)?;
+        }
+      }
+
+      ExportFormat::Binary => {
+        w.write_all(&BINARY_MAGIC)?;
+        w.write_all(&BINARY_VERSION.to_le_bytes())?;
+        w.write_all(&(self.values.len() as u32).to_le_bytes())?;
+        w.write_all(&self.start.to_le_bytes())?;
+        w.write_all(&self.end.to_le_bytes())?;
+
+        for x in &self.values {
+          w.write_all(&x.to_le_bytes())?;
+        }
+      }
+
+      ExportFormat::Rust => {
+        writeln!(w, "pub const {}_START: f32 = {:?};", name, self.start)?;
+        writeln!(w, "pub const {}_END: f32 = {:?};", name, self.end)?;
+        writeln!(w, "pub const {}: [f32; {}] = [", name, self.values.len())?;
+
+        for x in &self.values {
+          writeln!(w, "  {:?},", x)?;
+        }
+
+        writeln!(w, "];")?;
+      }
+
+      ExportFormat::Glsl => {
+        let n = self.values.len();
+
+        writeln!(w, "const float {}_START = {:?};", name, self.start)?;
+        writeln!(w, "const float {}_END = {:?};", name, self.end)?;
+        writeln!(w, "const float {}[{}] = float[{}](", name, n, n)?;
+
+        for (i, x) in self.values.iter().enumerate() {
+          let sep = if i + 1 < n { "," } else { "" };
+          writeln!(w, "  {:?}{}", x, sep)?;
+        }
+
+        writeln!(w, ");")?;
+      }
+    }
+
+    Ok(())
+  }
+
+  /// Write the table into a file, guessing the format from its extension.
+  ///
+  /// Source formats name their constants after the file stem, in upper case.
+  pub fn export(&self, path: impl AsRef<Path>) -> Result<(), ExportError> {
+    let path = path.as_ref();
+    let ext = path.extension().and_then(|ext| ext.to_str()).unwrap_or("");
+    let format = ExportFormat::from_extension(ext)
+      .ok_or_else(|| ExportError::UnknownFormat(ext.to_owned()))?;
+    let name = constant_name(path);
+
+    let file = File::create(path).map_err(ExportError::IOError)?;
+    let mut w = BufWriter::new(file);
+    self
+      .write(&mut w, format, &name)
+      .map_err(ExportError::IOError)?;
+    w.flush().map_err(ExportError::IOError)
+  }
+}
+
+/// Number of samples and time between them when sampling between `start` and `end`.
+fn sample_count(start: f32, end: f32, sampling: Sampling) -> Result<(usize, f32), ExportError> {
+  match sampling {
+    Sampling::Count(count) if (2..=MAX_SAMPLES).contains(&count) => {
+      Ok((count, (end - start) / (count - 1) as f32))
+    }
+
+    Sampling::Rate(rate) if rate.is_finite() && rate > 0. => {
+      // checked as a float, as the count could overflow
+      let intervals = ((end - start) * rate).floor();
+
+      if intervals < MAX_SAMPLES as f32 {
+        Ok((intervals as usize + 1, 1. / rate))
+      } else {
+        Err(ExportError::InvalidSampling(sampling))
+      }
+    }
+
+    _ => Err(ExportError::InvalidSampling(sampling)),
+  }
+}
+
+/// A color gradient sampled at a fixed rate, as a one-dimensional texture.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Ramp {
+  /// Time of the first texel.
+  pub start: f32,
+  /// Time of the last texel.
+  pub end: f32,
+  /// Sampled colors, regularly spaced in time.
+  pub texels: Vec<[u8; 4]>,
+}
+
+impl Ramp {
+  /// Sample the color of a gradient between its first and last keys.
+  pub fn bake(gradient: &Gradient, sampling: Sampling) -> Result<Self, ExportError> {
+    let (start, end) = gradient.range().ok_or(ExportError::EmptySpline)?;
+    let (count, step) = sample_count(start, end, sampling)?;
+
+    let texels = (0..count)
+      .map(|i| {
+        let t = start + step * i as f32;
+        gradient
+          .color(t)
+          .map(gradient::to_rgba8)
+          .ok_or(ExportError::CannotSample(t))
+      })
+      .collect::<Result<_, _>>()?;
+    let end = start + step * (count - 1) as f32;
+
+    Ok(Ramp { start, end, texels })
+  }
+
+  /// Write the ramp in the binary ramp format; see the [module documentation](self).
+  pub fn write(&self, mut w: impl Write) -> io::Result<()> {
+    w.write_all(&RAMP_MAGIC)?;
+    w.write_all(&RAMP_VERSION.to_le_bytes())?;
+    w.write_all(&(self.texels.len() as u32).to_le_bytes())?;
+    w.write_all(&self.start.to_le_bytes())?;
+    w.write_all(&self.end.to_le_bytes())?;
+
+    for texel in &self.texels {
+      w.write_all(texel)?;
+    }
+
+    Ok(())
+  }
+
+  /// Write the ramp into a file.
+  pub fn export(&self, path: impl AsRef<Path>) -> Result<(), ExportError> {
+    let file = File::create(path).map_err(ExportError::IOError)?;
+    let mut w = BufWriter::new(file);
+    self.write(&mut w).map_err(ExportError::IOError)?;
+    w.flush().map_err(ExportError::IOError)
+  }
+}
+
+/// Write the scalar version of a spline as JSON.
+pub fn write_spline(spline: &Spline<f32, ScreenPos>, w: impl Write) -> Result<(), ExportError> {
+  serde_json::to_writer_pretty(w, &scalar::to_scalar(spline)).map_err(ExportError::FormatError)
+}
+
+/// Write the 3D spline following a path as JSON.
+pub fn write_space(curve: &SpaceCurve, w: impl Write) -> Result<(), ExportError> {
+  serde_json::to_writer_pretty(w, &curve.to_spline()?).map_err(ExportError::FormatError)
+}
+
+/// Write the 3D spline following a path into a JSON file.
+pub fn export_space(curve: &SpaceCurve, path: impl AsRef<Path>) -> Result<(), ExportError> {
+  // don’t leave an empty file behind if the path cannot be converted
+  let spline = curve.to_spline()?;
+  let file = File::create(path).map_err(ExportError::IOError)?;
+  let mut w = BufWriter::new(file);
+  serde_json::to_writer_pretty(&mut w, &spline).map_err(ExportError::FormatError)?;
+  w.flush().map_err(ExportError::IOError)
+}
+
+/// Write the scalar version of a spline into a JSON file.
+pub fn export_spline(
+  spline: &Spline<f32, ScreenPos>,
+  path: impl AsRef<Path>,
+) -> Result<(), ExportError> {
+  let file = File::create(path).map_err(ExportError::IOError)?;
+  let mut w = BufWriter::new(file);
+  write_spline(spline, &mut w)?;
+  w.flush().map_err(ExportError::IOError)
+}
+
+/// Name of the constants generated for a file: its stem in upper case, with anything that cannot
+/// be part of an identifier replaced by underscores.
+fn constant_name(path: &Path) -> String {
+  let stem = path
+    .file_stem()
+    .and_then(|stem| stem.to_str())
+    .unwrap_or("");
+  let mut name: String = stem
+    .chars()
+    .map(|c| {
+      if c.is_ascii_alphanumeric() {
+        c.to_ascii_uppercase()
+      } else {
+        '_'
+      }
+    })
+    .collect();
+
+  if !name.starts_with(|c: char| c.is_ascii_alphabetic()) {
+    name.insert_str(0, "CURVE_");
+  }
+
+  name
+}
+
+/// Possible errors that might occur while exporting.
+#[derive(Debug)]
+pub enum ExportError {
+  /// There is nothing to sample.
+  EmptySpline,
+  /// The sampling doesn’t produce at least one sample.
+  InvalidSampling(Sampling),
+  /// The spline cannot be sampled at the given time.
+  CannotSample(f32),
+  /// The file extension doesn’t correspond to any known format.
+  UnknownFormat(String),
+  /// The file couldn’t be written.
+  IOError(io::Error),
+  /// The spline couldn’t be serialized.
+  FormatError(serde_json::Error),
+  /// The components of a 3D path don’t have keys with the same interpolation at the given time.
+  UnalignedComponents(f32),
+}
+
+impl fmt::Display for ExportError {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    match *self {
+      ExportError::EmptySpline => f.write_str("empty spline"),
+      ExportError::InvalidSampling(ref sampling) => write!(f, "invalid sampling: {:?}", sampling),
+      ExportError::CannotSample(t) => write!(f, "cannot sample the spline at t = {}", t),
+      ExportError::UnknownFormat(ref ext) => write!(f, "unknown export format: “{}”", ext),
+      ExportError::IOError(ref e) => write!(f, "I/O error: {}", e),
+      ExportError::FormatError(ref e) => write!(f, "cannot serialize the spline: {}", e),
+      ExportError::UnalignedComponents(t) => {
+        write!(f, "the x, y and z channels don’t share a key at t = {}", t)
+      }
+    }
+  }
+}
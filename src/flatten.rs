@@ -0,0 +1,211 @@
+//! Curve flattening.
+//!
+//! Curves are drawn as polylines. Rather than sampling at a fixed time step, each segment is
+//! recursively subdivided until its pieces are flat enough once mapped to view space by the
+//! camera, so that straight parts only get a few vertices while tight bends get many, at any zoom
+//! level.
+//!
+//! What gets drawn depends on the [`CurveMode`]: the graph of the vertical coordinate of the
+//! values as a function of time, or the path the values follow.
+
+use cgmath::InnerSpace as _;
+use splines::{Interpolation, Spline};
+
+use crate::{camera::Camera, mode::CurveMode, model::ScreenPos};
+
+/// Number of subdivisions always performed, so that S-shaped pieces whose midpoint happens to be
+/// on the chord are not mistaken for flat ones.
+const MIN_DEPTH: u32 = 3;
+/// Maximum number of subdivisions of a single segment.
+const MAX_DEPTH: u32 = 16;
+
+/// Check whether an interpolation is a Bézier one, whose key has handles.
+///
+/// A Bézier segment is cubic if the key ending it is a Bézier key too, as it provides an input
+/// handle, and quadratic otherwise.
+pub fn is_bezier(interpolation: &Interpolation<f32, ScreenPos>) -> bool {
+  matches!(
+    *interpolation,
+    Interpolation::Bezier(_) | Interpolation::StrokeBezier(..)
+  )
+}
+
+/// Point drawn at time `t` of the segment starting at key `i`, if it can be sampled.
+///
+/// `t` is clamped to the time range of the segment. The end of the segment is its limit when
+/// approaching the next key, so that discontinuous segments end where they should.
+pub fn segment_point(
+  spline: &Spline<f32, ScreenPos>,
+  mode: CurveMode,
+  i: usize,
+  t: f32,
+) -> Option<ScreenPos> {
+  let keys = spline.keys();
+  let (cp0, cp1) = (keys.get(i)?, keys.get(i + 1)?);
+
+  if t >= cp1.t {
+    return Some(key_point(mode, cp1.t, cp1.value));
+  }
+
+  let t = t.max(cp0.t);
+  let (p, _, _) = spline.sample_with_key(t)?;
+
+  Some(key_point(mode, t, p))
+}
+
+/// Point drawn for a value at time `t`.
+fn key_point(mode: CurveMode, t: f32, value: ScreenPos) -> ScreenPos {
+  match mode {
+    CurveMode::Function | CurveMode::Gradient | CurveMode::Space => ScreenPos::new(t, value.y),
+    CurveMode::Path => value,
+  }
+}
+
+/// Straight line between the ends of the segment starting at key `i`, found without sampling it.
+///
+/// This is what gets drawn in place of segments that cannot be sampled, such as Catmull-Rom
+/// segments lacking a key before or after them.
+pub fn segment_chord(
+  spline: &Spline<f32, ScreenPos>,
+  mode: CurveMode,
+  i: usize,
+) -> Option<[ScreenPos; 2]> {
+  let keys = spline.keys();
+  let cp0 = keys.get(i)?;
+  let end = segment_point(spline, mode, i, keys.get(i + 1)?.t)?;
+
+  Some([key_point(mode, cp0.t, cp0.value), end])
+}
+
+/// Flatten a spline into polylines.
+///
+/// The polylines deviate from the curve by at most about `tolerance`, measured in view space once
+/// mapped by `camera`. Segments that cannot be sampled break the curve, hence the several
+/// polylines.
+pub fn flatten(
+  spline: &Spline<f32, ScreenPos>,
+  mode: CurveMode,
+  camera: &Camera,
+  tolerance: f32,
+) -> Vec<Vec<ScreenPos>> {
+  let mut polylines = Vec::new();
+  let mut polyline: Vec<ScreenPos> = Vec::new();
+
+  for i in 0..spline.len().saturating_sub(1) {
+    match flatten_segment(spline, mode, i, camera, tolerance) {
+      // continue the current polyline, unless there’s a discontinuity
+      Some(segment) if polyline.last() == segment.first() => {
+        polyline.extend_from_slice(&segment[1..]);
+      }
+
+      Some(segment) => {
+        if polyline.len() > 1 {
+          polylines.push(polyline);
+        }
+
+        polyline = segment;
+      }
+
+      None => {
+        if polyline.len() > 1 {
+          polylines.push(polyline);
+        }
+
+        polyline = Vec::new();
+      }
+    }
+  }
+
+  if polyline.len() > 1 {
+    polylines.push(polyline);
+  }
+
+  polylines
+}
+
+/// Flatten the segment starting at key `i` into a polyline, if it can be sampled.
+///
+/// See [`flatten`] for the meaning of `camera` and `tolerance`.
+pub fn flatten_segment(
+  spline: &Spline<f32, ScreenPos>,
+  mode: CurveMode,
+  i: usize,
+  camera: &Camera,
+  tolerance: f32,
+) -> Option<Vec<ScreenPos>> {
+  let keys = spline.keys();
+  let (cp0, cp1) = (keys.get(i)?, keys.get(i + 1)?);
+  let start = segment_point(spline, mode, i, cp0.t)?;
+  let end = segment_point(spline, mode, i, cp1.t)?;
+  let mut polyline = vec![start];
+
+  if let Interpolation::Step(threshold) = cp0.interpolation {
+    match mode {
+      // a step is flat until its threshold, where it jumps
+      CurveMode::Function | CurveMode::Gradient | CurveMode::Space => {
+        let jump = cp0.t + (cp1.t - cp0.t) * threshold.clamp(0., 1.);
+        polyline.push(ScreenPos::new(jump, start.y));
+        polyline.push(ScreenPos::new(jump, end.y));
+      }
+
+      // the path stays on the first key until it jumps to the next one
+      CurveMode::Path => (),
+    }
+
+    polyline.push(end);
+  } else {
+    let f = |t| segment_point(spline, mode, i, t).unwrap_or(start);
+    subdivide(
+      &f,
+      camera,
+      tolerance,
+      (cp0.t, start),
+      (cp1.t, end),
+      0,
+      &mut polyline,
+    );
+  }
+
+  Some(polyline)
+}
+
+/// Subdivide the piece of curve between `a` and `b` until it’s flat enough, pushing the points of
+/// the resulting polyline — `b` included, `a` excluded.
+fn subdivide(
+  f: &impl Fn(f32) -> ScreenPos,
+  camera: &Camera,
+  tolerance: f32,
+  a: (f32, ScreenPos),
+  b: (f32, ScreenPos),
+  depth: u32,
+  out: &mut Vec<ScreenPos>,
+) {
+  let tm = (a.0 + b.0) * 0.5;
+  let m = (tm, f(tm));
+
+  let flat = depth >= MIN_DEPTH
+    && distance_to_chord(
+      camera.to_view(m.1),
+      camera.to_view(a.1),
+      camera.to_view(b.1),
+    ) <= tolerance;
+
+  if flat || depth >= MAX_DEPTH {
+    out.push(b.1);
+  } else {
+    subdivide(f, camera, tolerance, a, m, depth + 1, out);
+    subdivide(f, camera, tolerance, m, b, depth + 1, out);
+  }
+}
+
+/// Distance from `p` to the line segment between `a` and `b`.
+fn distance_to_chord(p: ScreenPos, a: ScreenPos, b: ScreenPos) -> f32 {
+  let ab = b - a;
+  let k = if ab.magnitude2() > 0. {
+    ((p - a).dot(ab) / ab.magnitude2()).clamp(0., 1.)
+  } else {
+    0.
+  };
+
+  (p - (a + ab * k)).magnitude()
+}
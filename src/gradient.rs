@@ -0,0 +1,168 @@
+//! Color gradients.
+//!
+//! In [`CurveMode::Gradient`](crate::mode::CurveMode::Gradient), a document describes a color
+//! ramp: the function curves of the channels named after [`COMPONENTS`] are the red, green, blue
+//! and alpha components of the color at each time, between 0 and 1. A missing or empty component
+//! takes its value in [`DEFAULT_COLOR`].
+//!
+//! Colors are edited by picking them in a [`Palette`], which sets all the components at once at
+//! the time of a key; see [`EditorModel::set_color`](crate::model::EditorModel::set_color).
+
+use cgmath::InnerSpace as _;
+use splines::Spline;
+
+use crate::model::ScreenPos;
+
+/// An RGBA color, each component being between 0 and 1.
+pub type Rgba = [f32; 4];
+
+/// Names of the channels holding the components of a gradient, in RGBA order, and the colors the
+/// channels are drawn with.
+pub const COMPONENTS: [(&str, [f32; 3]); 4] = [
+  ("red", [0.9, 0.3, 0.3]),
+  ("green", [0.3, 0.8, 0.3]),
+  ("blue", [0.35, 0.5, 1.]),
+  ("alpha", [0.7, 0.7, 0.7]),
+];
+
+/// Color of a gradient without any key: opaque black.
+pub const DEFAULT_COLOR: Rgba = [0., 0., 0., 1.];
+
+/// Number of hues of a palette.
+const PALETTE_HUES: usize = 12;
+/// Lightness of the colored rows of a palette, from top to bottom.
+const PALETTE_LIGHTNESS: [f32; 3] = [0.75, 0.5, 0.25];
+/// Distance between the centers of neighbouring swatches, in view units.
+const SWATCH_SPACING: f32 = 0.07;
+/// Radius of a swatch, in view units.
+pub const SWATCH_RADIUS: f32 = 0.03;
+
+/// A color gradient, read from the component curves of a set of channels.
+#[derive(Clone, Copy, Debug)]
+pub struct Gradient<'a> {
+  components: [Option<&'a Spline<f32, ScreenPos>>; 4],
+}
+
+impl<'a> Gradient<'a> {
+  /// Gradient made of the channels named after the [`COMPONENTS`], given as `(name, spline)`
+  /// pairs. The first channel of each name is used.
+  pub fn new<I>(channels: I) -> Self
+  where
+    I: IntoIterator<Item = (&'a str, &'a Spline<f32, ScreenPos>)>,
+  {
+    let mut components = [None; 4];
+
+    for (name, spline) in channels {
+      if let Some(c) = component_index(name) {
+        components[c] = components[c].or(Some(spline));
+      }
+    }
+
+    Gradient { components }
+  }
+
+  /// Color at time `t`; before the first key and after the last one, the color of the closest key.
+  ///
+  /// `None` is returned if a component cannot be sampled at that time.
+  pub fn color(&self, t: f32) -> Option<Rgba> {
+    let mut color = DEFAULT_COLOR;
+
+    for (x, spline) in color.iter_mut().zip(&self.components) {
+      if let Some(spline) = spline.filter(|spline| !spline.is_empty()) {
+        *x = spline.clamped_sample(t)?.y.clamp(0., 1.);
+      }
+    }
+
+    Some(color)
+  }
+
+  /// Times of the first and last keys of all the components, if any.
+  pub fn range(&self) -> Option<(f32, f32)> {
+    self
+      .components
+      .iter()
+      .flatten()
+      .flat_map(|spline| spline.keys())
+      .fold(None, |range, key| match range {
+        Some((start, end)) => Some((key.t.min(start), key.t.max(end))),
+        None => Some((key.t, key.t)),
+      })
+  }
+}
+
+/// Index of the component a channel holds, from its name.
+pub fn component_index(name: &str) -> Option<usize> {
+  COMPONENTS.iter().position(|&(n, _)| n == name)
+}
+
+/// Quantize a color to 8 bits per component.
+pub fn to_rgba8(color: Rgba) -> [u8; 4] {
+  color.map(|x| (x.clamp(0., 1.) * 255.).round() as u8)
+}
+
+/// Hexadecimal notation of a color, as in `#ff8000ff`.
+pub fn hex(color: Rgba) -> String {
+  let [r, g, b, a] = to_rgba8(color);
+  format!("#{:02x}{:02x}{:02x}{:02x}", r, g, b, a)
+}
+
+/// Color from its hue, saturation and lightness, all between 0 and 1.
+fn hsl(h: f32, s: f32, l: f32) -> [f32; 3] {
+  let chroma = (1. - (2. * l - 1.).abs()) * s;
+  let channel = |n: f32| {
+    let k = (n + h * 12.) % 12.;
+    l - chroma * 0.5 * (k - 3.).min(9. - k).clamp(-1., 1.)
+  };
+
+  [channel(0.), channel(8.), channel(4.)]
+}
+
+/// Swatches to pick colors from, in the top left corner of the view.
+///
+/// A row of grays lies under rows of saturated hues of decreasing lightness.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Palette {
+  swatches: Vec<(ScreenPos, [f32; 3])>,
+}
+
+impl Palette {
+  /// Lay out the swatches for a view of the given aspect ratio.
+  pub fn new(aspect_ratio: f32) -> Self {
+    let origin = ScreenPos::new(
+      -aspect_ratio + 2. * SWATCH_SPACING,
+      1. - 2. * SWATCH_SPACING,
+    );
+    let at = |column: usize, row: usize| {
+      origin + ScreenPos::new(column as f32, -(row as f32)) * SWATCH_SPACING
+    };
+    let mut swatches = Vec::new();
+
+    for (row, &l) in PALETTE_LIGHTNESS.iter().enumerate() {
+      for column in 0..PALETTE_HUES {
+        let h = column as f32 / PALETTE_HUES as f32;
+        swatches.push((at(column, row), hsl(h, 1., l)));
+      }
+    }
+
+    for column in 0..PALETTE_HUES {
+      let l = column as f32 / (PALETTE_HUES - 1) as f32;
+      swatches.push((at(column, PALETTE_LIGHTNESS.len()), [l; 3]));
+    }
+
+    Palette { swatches }
+  }
+
+  /// View-space positions and colors of the swatches.
+  pub fn swatches(&self) -> &[(ScreenPos, [f32; 3])] {
+    &self.swatches
+  }
+
+  /// Color of the swatch under a view-space position, if any.
+  pub fn pick(&self, view_pos: ScreenPos) -> Option<[f32; 3]> {
+    self
+      .swatches
+      .iter()
+      .find(|&&(p, _)| (p - view_pos).magnitude2() <= SWATCH_RADIUS * SWATCH_RADIUS)
+      .map(|&(_, color)| color)
+  }
+}
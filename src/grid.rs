@@ -0,0 +1,146 @@
+//! Background grid.
+//!
+//! The grid is made of major lines, regularly spaced in curve space, each major cell being
+//! subdivided by minor lines. Two extra lines materialize the axes (t = 0 and value = 0).
+
+use cgmath::Vector2;
+
+use crate::model::ScreenPos;
+
+/// Grid definition.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Grid {
+  /// Distance between two major lines, in curve space, per axis.
+  pub major_step: Vector2<f32>,
+  /// Number of minor cells in a major cell.
+  pub subdivisions: u32,
+}
+
+impl Default for Grid {
+  fn default() -> Self {
+    Grid {
+      major_step: Vector2::new(0.1, 0.25),
+      subdivisions: 5,
+    }
+  }
+}
+
+impl Grid {
+  /// Distance between two minor lines, in curve space, per axis.
+  pub fn minor_step(&self) -> Vector2<f32> {
+    self.major_step / self.subdivisions.max(1) as f32
+  }
+
+  /// Snap a position to the closest intersection of minor lines.
+  pub fn snap(&self, p: ScreenPos) -> ScreenPos {
+    let step = self.minor_step();
+    ScreenPos::new(snap_to_step(p.x, step.x), snap_to_step(p.y, step.y))
+  }
+
+  /// Lines crossing the area between `min` and `max`.
+  ///
+  /// At most about `max_lines` lines are generated per axis: minor lines are dropped first if
+  /// they are too dense, then major lines are thinned out. Axis lines are always present when
+  /// visible.
+  pub fn lines(&self, min: ScreenPos, max: ScreenPos, max_lines: u32) -> Vec<GridLine> {
+    let mut lines = Vec::new();
+    let minor_step = self.minor_step();
+
+    for &(orientation, lo, hi, major, minor) in &[
+      (
+        Orientation::Vertical,
+        min.x,
+        max.x,
+        self.major_step.x,
+        minor_step.x,
+      ),
+      (
+        Orientation::Horizontal,
+        min.y,
+        max.y,
+        self.major_step.y,
+        minor_step.y,
+      ),
+    ] {
+      if major <= 0. || hi <= lo {
+        continue;
+      }
+
+      let fits = |step: f32| (hi - lo) / step <= max_lines as f32;
+
+      // pick the finest level fitting the budget
+      let (step, major_every) = if fits(minor) {
+        (minor, self.subdivisions.max(1) as i64)
+      } else {
+        let mut step = major;
+
+        while !fits(step) {
+          step *= 2.;
+        }
+
+        (step, 1)
+      };
+
+      let first = (lo / step).ceil() as i64;
+      let last = (hi / step).floor() as i64;
+
+      for n in first..=last {
+        let kind = if n == 0 {
+          LineKind::Axis
+        } else if n % major_every == 0 {
+          LineKind::Major
+        } else {
+          LineKind::Minor
+        };
+
+        lines.push(GridLine {
+          orientation,
+          pos: n as f32 * step,
+          kind,
+        });
+      }
+    }
+
+    lines
+  }
+}
+
+/// Round a value to the closest multiple of `step`.
+fn snap_to_step(x: f32, step: f32) -> f32 {
+  if step > 0. {
+    (x / step).round() * step
+  } else {
+    x
+  }
+}
+
+/// A line of the grid.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct GridLine {
+  /// Orientation of the line.
+  pub orientation: Orientation,
+  /// Position of the line on the axis it crosses: X for vertical lines, Y for horizontal ones.
+  pub pos: f32,
+  /// Kind of line.
+  pub kind: LineKind,
+}
+
+/// Orientation of a grid line.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Orientation {
+  /// Line of constant t.
+  Vertical,
+  /// Line of constant value.
+  Horizontal,
+}
+
+/// Kind of grid line.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum LineKind {
+  /// Line subdividing a major cell.
+  Minor,
+  /// Line delimiting major cells.
+  Major,
+  /// t = 0 or value = 0.
+  Axis,
+}
@@ -0,0 +1,94 @@
+//! Undo / redo history.
+//!
+//! The history stores snapshots of the edited state. A snapshot is taken when a transaction
+//! begins; when it’s committed, the snapshot is pushed onto the undo stack if the state has changed
+//! in the meantime. That allows to coalesce several mutations — e.g. all the moves of a mouse
+//! drag — into a single history entry.
+
+use std::collections::VecDeque;
+
+/// Bounded undo / redo history.
+#[derive(Debug)]
+pub struct History<S> {
+  // Past states, the most recent one being at the back.
+  undo: VecDeque<S>,
+  // Undone states, the most recent one being at the back.
+  redo: Vec<S>,
+  // Maximum number of undo entries.
+  depth: usize,
+  // State captured when the current transaction began, if any.
+  pending: Option<S>,
+}
+
+impl<S> History<S>
+where
+  S: PartialEq,
+{
+  /// Create an empty history able to undo at most `depth` transactions.
+  pub fn new(depth: usize) -> Self {
+    History {
+      undo: VecDeque::with_capacity(depth),
+      redo: Vec::new(),
+      depth,
+      pending: None,
+    }
+  }
+
+  /// Whether a transaction is currently open.
+  pub fn is_recording(&self) -> bool {
+    self.pending.is_some()
+  }
+
+  /// Begin a transaction with the state before any mutation.
+  ///
+  /// If a transaction is already open, this function does nothing, so that the outermost
+  /// transaction wins.
+  pub fn begin(&mut self, state: S) {
+    if self.pending.is_none() {
+      self.pending = Some(state);
+    }
+  }
+
+  /// Commit the current transaction, if any, given the state after all its mutations.
+  ///
+  /// Nothing is recorded if the state hasn’t changed.
+  pub fn commit(&mut self, state: &S) {
+    if let Some(before) = self.pending.take() {
+      if before != *state {
+        self.push_undo(before);
+        self.redo.clear();
+      }
+    }
+  }
+
+  /// Undo the last transaction, given the current state. The state to restore is returned, if any.
+  pub fn undo(&mut self, current: S) -> Option<S> {
+    let state = self.undo.pop_back()?;
+    self.redo.push(current);
+    Some(state)
+  }
+
+  /// Redo the last undone transaction, given the current state. The state to restore is returned,
+  /// if any.
+  pub fn redo(&mut self, current: S) -> Option<S> {
+    let state = self.redo.pop()?;
+    self.push_undo(current);
+    Some(state)
+  }
+
+  /// Push a past state, dropping the oldest ones beyond the depth.
+  fn push_undo(&mut self, state: S) {
+    self.undo.push_back(state);
+
+    while self.undo.len() > self.depth {
+      self.undo.pop_front();
+    }
+  }
+
+  /// Forget everything.
+  pub fn clear(&mut self) {
+    self.undo.clear();
+    self.redo.clear();
+    self.pending = None;
+  }
+}
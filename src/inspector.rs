@@ -0,0 +1,316 @@
+//! Numeric key inspector.
+//!
+//! The inspector describes the inspected key — the key of the first selected element — and lets
+//! the user type exact coordinates for it, one field at a time. Typed values are applied through
+//! the regular editing operations of [`EditorModel`], so they get recorded in the history just
+//! like mouse edits.
+
+use splines::{Interpolation, Key};
+use std::fmt;
+
+use crate::{
+  gradient,
+  interpolation::InterpolationKind,
+  mode::CurveMode,
+  model::{EditorError, EditorModel, HandleSelection, ScreenPos, Selection},
+};
+
+/// Key inspector.
+#[derive(Debug, Default)]
+pub struct Inspector {
+  // Field being typed, if any, along with the text typed so far.
+  editing: Option<(Field, String)>,
+}
+
+impl Inspector {
+  /// Create an inspector, not editing anything.
+  pub fn new() -> Self {
+    Inspector { editing: None }
+  }
+
+  /// Index of the inspected key, if any.
+  pub fn inspected_key(model: &EditorModel) -> Option<usize> {
+    model.selection().first().map(|s| s.key())
+  }
+
+  /// Check whether a field is being typed.
+  pub fn is_editing(&self) -> bool {
+    self.editing.is_some()
+  }
+
+  /// Field being typed, if any.
+  pub fn field(&self) -> Option<Field> {
+    self.editing.as_ref().map(|(field, _)| *field)
+  }
+
+  /// Start typing the first field of the inspected key — or its threshold if its threshold
+  /// marker is selected. Return whether a key is inspected.
+  pub fn begin(&mut self, model: &EditorModel) -> bool {
+    let field = match model.selection().first() {
+      Some(Selection::Threshold(_)) => Field::Threshold,
+      _ => Field::Time,
+    };
+
+    self.editing = Self::inspected_key(model).map(|_| (field, String::new()));
+    self.editing.is_some()
+  }
+
+  /// Stop typing, discarding the text typed in the current field.
+  pub fn cancel(&mut self) {
+    self.editing = None;
+  }
+
+  /// Type a character in the current field. Characters that cannot be part of a number are
+  /// ignored.
+  pub fn input(&mut self, c: char) {
+    if let Some((_, ref mut text)) = self.editing {
+      if c.is_ascii_digit() || "+-.eE".contains(c) {
+        text.push(c);
+      }
+    }
+  }
+
+  /// Remove the last character typed in the current field.
+  pub fn erase(&mut self) {
+    if let Some((_, ref mut text)) = self.editing {
+      text.pop();
+    }
+  }
+
+  /// Apply the current field and move to the next one, cycling.
+  pub fn next_field(&mut self, model: &mut EditorModel) -> Result<(), InspectorError> {
+    self.apply(model)?;
+
+    if let Some((ref mut field, ref mut text)) = self.editing {
+      let fields = Self::inspected_fields(model);
+      let next = fields
+        .iter()
+        .position(|f| f == field)
+        .map_or(0, |i| (i + 1) % fields.len());
+
+      *field = fields[next];
+      text.clear();
+    }
+
+    Ok(())
+  }
+
+  /// Apply the current field and stop typing.
+  ///
+  /// If the typed text is not a valid number, the inspector keeps editing so that it can be fixed.
+  pub fn commit(&mut self, model: &mut EditorModel) -> Result<(), InspectorError> {
+    self.apply(model)?;
+    self.editing = None;
+    Ok(())
+  }
+
+  /// Apply the text typed in the current field, if any, to the inspected key.
+  fn apply(&mut self, model: &mut EditorModel) -> Result<(), InspectorError> {
+    let (field, text) = match self.editing {
+      Some((field, ref text)) if !text.is_empty() => (field, text),
+      _ => return Ok(()),
+    };
+
+    let index = match Self::inspected_key(model) {
+      Some(index) => index,
+      None => {
+        // the inspected key went away (undo, removal…)
+        self.editing = None;
+        return Ok(());
+      }
+    };
+
+    // overflowing numbers such as 1e39 parse as infinities
+    let x = text
+      .parse::<f32>()
+      .ok()
+      .filter(|x| x.is_finite())
+      .ok_or_else(|| InspectorError::InvalidNumber(text.clone()))?;
+    let key = model.spline().keys()[index];
+
+    match field {
+      Field::Time => model.set_key_time(index, x),
+      Field::ValueX => model.move_keys(&[(index, ScreenPos::new(x, key.value.y))]),
+      Field::Value => model.move_keys(&[(index, ScreenPos::new(key.value.x, x))]),
+
+      Field::HandleX(h) | Field::HandleY(h) => match Self::handle(&key, h) {
+        Some(mut p) => {
+          if let Field::HandleX(_) = field {
+            p.x = x;
+          } else {
+            p.y = x;
+          }
+
+          model.set_handle(index, p, h)
+        }
+
+        None => Err(EditorError::WrongInterpolationAssumed(index)),
+      },
+
+      Field::Threshold => model.set_threshold(index, x),
+    }
+    .map_err(InspectorError::EditorError)?;
+
+    if let Some((_, ref mut text)) = self.editing {
+      text.clear();
+    }
+
+    Ok(())
+  }
+
+  /// Fields that can be typed for the inspected key.
+  fn inspected_fields(model: &EditorModel) -> Vec<Field> {
+    let key = Self::inspected_key(model).map(|i| model.spline().keys()[i]);
+    let mut fields = match model.mode() {
+      CurveMode::Function | CurveMode::Gradient | CurveMode::Space => {
+        vec![Field::Time, Field::Value]
+      }
+      CurveMode::Path => vec![Field::Time, Field::ValueX, Field::Value],
+    };
+
+    match key.map(|key| key.interpolation) {
+      Some(Interpolation::Bezier(_)) => {
+        fields.push(Field::HandleX(HandleSelection::Own));
+        fields.push(Field::HandleY(HandleSelection::Own));
+      }
+
+      Some(Interpolation::StrokeBezier(..)) => {
+        for &h in &[HandleSelection::Own, HandleSelection::Mirror] {
+          fields.push(Field::HandleX(h));
+          fields.push(Field::HandleY(h));
+        }
+      }
+
+      Some(Interpolation::Step(_)) => fields.push(Field::Threshold),
+
+      _ => (),
+    }
+
+    fields
+  }
+
+  /// Position of a handle of a key, if it has handles.
+  fn handle(key: &Key<f32, ScreenPos>, h: HandleSelection) -> Option<ScreenPos> {
+    match (key.interpolation, h) {
+      (Interpolation::Bezier(u), HandleSelection::Own) => Some(u),
+      (Interpolation::Bezier(u), HandleSelection::Mirror) => Some(2. * key.value - u),
+      (Interpolation::StrokeBezier(input, _), HandleSelection::Own) => Some(input),
+      (Interpolation::StrokeBezier(_, output), HandleSelection::Mirror) => Some(output),
+      _ => None,
+    }
+  }
+
+  /// Describe the inspected key, if any, on a single line.
+  ///
+  /// The field being typed is shown between brackets with the text typed so far, or its current
+  /// value if nothing has been typed yet.
+  pub fn describe(&self, model: &EditorModel) -> Option<String> {
+    let index = Self::inspected_key(model)?;
+    let key = model.spline().keys().get(index)?;
+
+    let show = |field: Field, x: f32| match self.editing {
+      Some((f, ref text)) if f == field && !text.is_empty() => format!("[{}_]", text),
+      Some((f, _)) if f == field => format!("[{:.3}]", x),
+      _ => format!("{:.3}", x),
+    };
+    let show_handle = |name: &str, h: HandleSelection| {
+      let p = Self::handle(key, h).unwrap_or(key.value);
+      format!(
+        "  {}: ({}, {})",
+        name,
+        show(Field::HandleX(h), p.x),
+        show(Field::HandleY(h), p.y)
+      )
+    };
+
+    let value = match model.mode() {
+      CurveMode::Function | CurveMode::Gradient | CurveMode::Space => {
+        show(Field::Value, key.value.y)
+      }
+      CurveMode::Path => format!(
+        "({}, {})",
+        show(Field::ValueX, key.value.x),
+        show(Field::Value, key.value.y)
+      ),
+    };
+    let mut desc = format!(
+      "key {} — t: {}  value: {}  {}",
+      index,
+      show(Field::Time, key.t),
+      value,
+      interpolation_name(&key.interpolation)
+    );
+
+    match key.interpolation {
+      Interpolation::Step(threshold) => {
+        desc += &format!("  threshold: {}", show(Field::Threshold, threshold));
+      }
+
+      Interpolation::Bezier(_) => {
+        desc += &show_handle("handle", HandleSelection::Own);
+      }
+
+      Interpolation::StrokeBezier(..) => {
+        desc += &show_handle("in", HandleSelection::Own);
+        desc += &show_handle("out", HandleSelection::Mirror);
+
+        if let Some(mode) = model.tangent_mode(index) {
+          desc += &format!("  {}", mode.name());
+        }
+      }
+
+      _ => (),
+    }
+
+    // the color the key contributes to
+    if model.mode() == CurveMode::Gradient {
+      if let Some(color) = model.gradient().color(key.t) {
+        desc += &format!("  color: {}", gradient::hex(color));
+      }
+    }
+
+    Some(desc)
+  }
+}
+
+/// Short, human-readable name of an interpolation mode.
+pub fn interpolation_name(interpolation: &Interpolation<f32, ScreenPos>) -> String {
+  InterpolationKind::of(interpolation)
+    .map_or("unknown", InterpolationKind::name)
+    .to_owned()
+}
+
+/// A field of the inspector.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Field {
+  /// Time of the key.
+  Time,
+  /// Horizontal coordinate of the value of the key, only typed in [`CurveMode::Path`].
+  ValueX,
+  /// Value of the key; its vertical coordinate in [`CurveMode::Path`].
+  Value,
+  /// X coordinate of a handle.
+  HandleX(HandleSelection),
+  /// Y coordinate of a handle.
+  HandleY(HandleSelection),
+  /// Threshold of a step key.
+  Threshold,
+}
+
+/// Possible errors that might occur while typing in the inspector.
+#[derive(Debug)]
+pub enum InspectorError {
+  /// The typed text is not a number.
+  InvalidNumber(String),
+  /// The typed value could not be applied.
+  EditorError(EditorError),
+}
+
+impl fmt::Display for InspectorError {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    match *self {
+      InspectorError::InvalidNumber(ref text) => write!(f, "invalid number: {}", text),
+      InspectorError::EditorError(ref e) => write!(f, "cannot apply value: {}", e),
+    }
+  }
+}
@@ -0,0 +1,257 @@
+//! Interpolation modes and conversions between them.
+//!
+//! Switching the interpolation of a key changes the segment starting at that key, and might also
+//! change the previous one when Bézier handles appear or disappear. [`convert`] picks the new
+//! handles so that the curve keeps its shape as much as possible: Bézier handles follow the
+//! tangent the curve had, and Bézier segments whose degree changes are degree-elevated or reduced.
+
+use splines::{Interpolation, Key, Spline};
+
+use crate::{
+  flatten::{is_bezier, segment_point},
+  mode::CurveMode,
+  model::ScreenPos,
+};
+
+/// Threshold given to keys switching to [`Interpolation::Step`].
+const DEFAULT_STEP_THRESHOLD: f32 = 0.5;
+
+/// Interpolation mode, regardless of its parameters.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum InterpolationKind {
+  /// [`Interpolation::Step`].
+  Step,
+  /// [`Interpolation::Linear`].
+  Linear,
+  /// [`Interpolation::Cosine`].
+  Cosine,
+  /// [`Interpolation::Bezier`].
+  Bezier,
+  /// [`Interpolation::StrokeBezier`].
+  StrokeBezier,
+  /// [`Interpolation::CatmullRom`].
+  CatmullRom,
+}
+
+impl InterpolationKind {
+  /// All the modes, in cycling order.
+  pub const ALL: [InterpolationKind; 6] = [
+    InterpolationKind::Step,
+    InterpolationKind::Linear,
+    InterpolationKind::Cosine,
+    InterpolationKind::Bezier,
+    InterpolationKind::StrokeBezier,
+    InterpolationKind::CatmullRom,
+  ];
+
+  /// Mode of an interpolation, if it’s supported.
+  pub fn of(interpolation: &Interpolation<f32, ScreenPos>) -> Option<Self> {
+    match *interpolation {
+      Interpolation::Step(_) => Some(InterpolationKind::Step),
+      Interpolation::Linear => Some(InterpolationKind::Linear),
+      Interpolation::Cosine => Some(InterpolationKind::Cosine),
+      Interpolation::CatmullRom => Some(InterpolationKind::CatmullRom),
+      Interpolation::Bezier(_) => Some(InterpolationKind::Bezier),
+      Interpolation::StrokeBezier(..) => Some(InterpolationKind::StrokeBezier),
+      _ => None,
+    }
+  }
+
+  /// Next mode in cycling order.
+  pub fn next(self) -> Self {
+    let i = Self::ALL.iter().position(|&kind| kind == self).unwrap_or(0);
+    Self::ALL[(i + 1) % Self::ALL.len()]
+  }
+
+  /// Short, human-readable name.
+  pub fn name(self) -> &'static str {
+    match self {
+      InterpolationKind::Step => "step",
+      InterpolationKind::Linear => "linear",
+      InterpolationKind::Cosine => "cosine",
+      InterpolationKind::CatmullRom => "catmull-rom",
+      InterpolationKind::Bezier => "bézier",
+      InterpolationKind::StrokeBezier => "stroke bézier",
+    }
+  }
+}
+
+/// Switch the key at index `i` to another interpolation mode, keeping the shape of the curve —
+/// drawn according to `mode` — as much as possible.
+///
+/// Nothing changes if the key already uses that mode, so that its handles are kept. The key before
+/// it might be changed too: if it starts a Bézier segment, that segment becomes cubic or quadratic
+/// depending on whether the key at `i` gets an input handle, and its control points are adjusted
+/// accordingly. A [`Interpolation::Bezier`] key before it becomes a
+/// [`Interpolation::StrokeBezier`] one in that case, so that its own input handle doesn’t move.
+pub fn convert(
+  keys: &mut [Key<f32, ScreenPos>],
+  mode: CurveMode,
+  i: usize,
+  kind: InterpolationKind,
+) {
+  let key = match keys.get(i) {
+    Some(key) if InterpolationKind::of(&key.interpolation) != Some(kind) => *key,
+    _ => return,
+  };
+  let spline = Spline::from_vec(keys.to_vec());
+  let p = key.value;
+
+  // output handle of the previous key, if it starts a Bézier segment
+  let previous = i
+    .checked_sub(1)
+    .map(|j| keys[j])
+    .and_then(|prev| output_handle_of(&prev.interpolation).map(|u| (prev, u)));
+
+  let interpolation = match kind {
+    InterpolationKind::Step => Interpolation::Step(DEFAULT_STEP_THRESHOLD),
+    InterpolationKind::Linear => Interpolation::Linear,
+    InterpolationKind::Cosine => Interpolation::Cosine,
+    InterpolationKind::CatmullRom => Interpolation::CatmullRom,
+
+    InterpolationKind::Bezier => match key.interpolation {
+      Interpolation::StrokeBezier(_, output) => Interpolation::Bezier(output),
+      _ => Interpolation::Bezier(output_handle(&spline, mode, i)),
+    },
+
+    InterpolationKind::StrokeBezier => match key.interpolation {
+      Interpolation::Bezier(output) => Interpolation::StrokeBezier(2. * p - output, output),
+
+      _ => {
+        let input = match previous {
+          // the previous segment is quadratic; this is its exact cubic equivalent
+          Some((_, u)) => p + (u - p) * (2. / 3.),
+          None => input_handle(&spline, mode, i),
+        };
+
+        Interpolation::StrokeBezier(input, output_handle(&spline, mode, i))
+      }
+    },
+  };
+
+  keys[i].interpolation = interpolation;
+
+  // adjust the previous Bézier segment if its degree changed
+  if let Some((prev, u)) = previous {
+    let a = prev.value;
+    let output = match (is_bezier(&key.interpolation), is_bezier(&interpolation)) {
+      // quadratic to cubic: degree elevation
+      (false, true) => a + (u - a) * (2. / 3.),
+
+      // cubic to quadratic: least-squares degree reduction
+      (true, false) => {
+        let c2 = input_handle_of(&key).unwrap_or(p);
+        ((u + c2) * 3. - (a + p)) * 0.25
+      }
+
+      _ => return,
+    };
+
+    let input = match prev.interpolation {
+      Interpolation::StrokeBezier(input, _) => input,
+      _ => 2. * a - u,
+    };
+
+    keys[i - 1].interpolation = Interpolation::StrokeBezier(input, output);
+  }
+}
+
+/// Output handle of a key starting a Bézier segment.
+fn output_handle_of(interpolation: &Interpolation<f32, ScreenPos>) -> Option<ScreenPos> {
+  match *interpolation {
+    Interpolation::Bezier(u) | Interpolation::StrokeBezier(_, u) => Some(u),
+    _ => None,
+  }
+}
+
+/// Input handle of a key ending a cubic Bézier segment.
+fn input_handle_of(key: &Key<f32, ScreenPos>) -> Option<ScreenPos> {
+  match key.interpolation {
+    Interpolation::Bezier(u) => Some(2. * key.value - u),
+    Interpolation::StrokeBezier(input, _) => Some(input),
+    _ => None,
+  }
+}
+
+/// Output handle following the curve as it leaves the key at index `i`.
+///
+/// The segment is cubic if the next key has an input handle and quadratic otherwise; the handle
+/// is placed a third of the segment away in the former case and half of it in the latter, so that
+/// straight segments stay straight.
+fn output_handle(spline: &Spline<f32, ScreenPos>, mode: CurveMode, i: usize) -> ScreenPos {
+  let keys = spline.keys();
+  let p = keys[i].value;
+
+  match keys.get(i + 1) {
+    Some(next) => {
+      let dt = next.t - keys[i].t;
+      let k = if is_bezier(&next.interpolation) {
+        1. / 3.
+      } else {
+        0.5
+      };
+
+      p + velocity(spline, mode, i, 0.) * dt * k
+    }
+
+    // nothing to follow: mirror the input handle
+    None if i > 0 => 2. * p - input_handle(spline, mode, i),
+    None => p + ScreenPos::new(0.1, 0.),
+  }
+}
+
+/// Input handle following the curve as it reaches the key at index `i`, a third of the previous
+/// segment away.
+fn input_handle(spline: &Spline<f32, ScreenPos>, mode: CurveMode, i: usize) -> ScreenPos {
+  let keys = spline.keys();
+  let p = keys[i].value;
+
+  match i.checked_sub(1).map(|j| keys[j]) {
+    Some(prev) => {
+      let dt = keys[i].t - prev.t;
+      p - velocity(spline, mode, i - 1, 1.) * dt / 3.
+    }
+
+    None => p - ScreenPos::new(0.1, 0.),
+  }
+}
+
+/// Derivative with respect to time of the curve drawn according to `mode`, at the normalized time
+/// `s` of the segment starting at key `i`.
+///
+/// In [`CurveMode::Function`], the horizontal coordinate is the time itself, so its derivative is
+/// exactly 1. Segments that cannot be sampled fall back to the derivative along their chord, and
+/// segments without duration to a null one.
+fn velocity(spline: &Spline<f32, ScreenPos>, mode: CurveMode, i: usize, s: f32) -> ScreenPos {
+  let keys = spline.keys();
+  let (cp0, cp1) = (keys[i], keys[i + 1]);
+  let dt = cp1.t - cp0.t;
+
+  if dt <= 0. {
+    return match mode {
+      CurveMode::Function | CurveMode::Gradient | CurveMode::Space => ScreenPos::new(1., 0.),
+      CurveMode::Path => ScreenPos::new(0., 0.),
+    };
+  }
+
+  let chord = (cp1.value - cp0.value) / dt;
+
+  // one-sided, second-order finite difference, staying inside of the segment
+  let h = if s < 0.5 { dt * 1e-2 } else { -dt * 1e-2 };
+  let t = cp0.t + dt * s.clamp(0., 1.);
+  let points = (
+    segment_point(spline, mode, i, t),
+    segment_point(spline, mode, i, t + h),
+    segment_point(spline, mode, i, t + 2. * h),
+  );
+
+  let v = match points {
+    (Some(a), Some(b), Some(c)) if h != 0. => (b * 4. - a * 3. - c) / (2. * h),
+    _ => chord,
+  };
+
+  match mode {
+    CurveMode::Function | CurveMode::Gradient | CurveMode::Space => ScreenPos::new(1., v.y),
+    CurveMode::Path => v,
+  }
+}
@@ -0,0 +1,23 @@
+//! Spline editing, independent of any rendering backend.
+//!
+//! This library contains everything needed to edit a spline — the editing model, the document
+//! format and the undo / redo history — without requiring a graphics context. The `spline-editor`
+//! binary provides the interactive, luminance-based view on top of it.
+
+pub mod camera;
+pub mod coords;
+pub mod document;
+pub mod export;
+pub mod flatten;
+pub mod gradient;
+pub mod grid;
+pub mod history;
+pub mod inspector;
+pub mod interpolation;
+pub mod mode;
+pub mod model;
+pub mod scalar;
+pub mod slots;
+pub mod snap;
+pub mod space;
+pub mod tangent;
@@ -0,0 +1,21 @@
+in vec2 g_point;
+in vec2 g_a;
+in vec2 g_b;
+in float g_half_width;
+in vec3 g_color;
+
+out vec4 frag;
+
+void main() {
+  // distance to the segment; joins and caps get round for free
+  vec2 pa = g_point - g_a;
+  vec2 ba = g_b - g_a;
+  float h = clamp(dot(pa, ba) / max(dot(ba, ba), 1e-12), 0., 1.);
+  float d = length(pa - ba * h);
+
+  // analytic anti-aliasing over about a pixel
+  float aa = max(fwidth(d), 1e-6);
+  float alpha = clamp((g_half_width - d) / aa + 0.5, 0., 1.);
+
+  frag = vec4(g_color, alpha);
+}
@@ -0,0 +1,46 @@
+layout (lines) in;
+layout (triangle_strip, max_vertices = 4) out;
+
+in vec3 v_color[];
+in float v_half_width[];
+
+out vec2 g_point;
+out vec2 g_a;
+out vec2 g_b;
+out float g_half_width;
+out vec3 g_color;
+
+uniform float aspect_ratio;
+uniform float pixel_size;
+
+vec4 to_ndc(vec2 p) {
+  return vec4(p.x / aspect_ratio, p.y, 0., 1.);
+}
+
+void main() {
+  vec2 a = gl_in[0].gl_Position.xy;
+  vec2 b = gl_in[1].gl_Position.xy;
+  float half_width = max(v_half_width[0], v_half_width[1]);
+
+  // the quad covers the whole capsule around the segment, plus a pixel for anti-aliasing
+  float r = half_width + pixel_size;
+  vec2 dir = b - a;
+  float len = length(dir);
+  dir = len > 0. ? dir / len : vec2(1., 0.);
+  vec2 n = vec2(-dir.y, dir.x);
+
+  vec2 corners[4] = vec2[4](a - (dir + n) * r, a - (dir - n) * r, b + (dir - n) * r, b + (dir + n) * r);
+  vec3 colors[4] = vec3[4](v_color[0], v_color[0], v_color[1], v_color[1]);
+
+  for (int i = 0; i < 4; ++i) {
+    g_point = corners[i];
+    g_a = a;
+    g_b = b;
+    g_half_width = half_width;
+    g_color = colors[i];
+    gl_Position = to_ndc(g_point);
+    EmitVertex();
+  }
+
+  EndPrimitive();
+}
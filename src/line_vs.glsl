@@ -0,0 +1,17 @@
+in vec2 position;
+in vec3 color;
+in float width;
+
+out vec3 v_color;
+out float v_half_width;
+
+uniform vec2 view_center;
+uniform vec2 view_scale;
+uniform float pixel_size;
+
+void main() {
+  v_color = color;
+  v_half_width = 0.5 * width * pixel_size;
+  // the position is kept in view space; the geometry shader applies the aspect ratio
+  gl_Position = vec4((position - view_center) * view_scale, 0., 1.);
+}
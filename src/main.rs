@@ -1,14 +1,13 @@
 // This is currently a prototype. The current code is pretty naive, especially in terms of
 // allocation of keys in splines. Some  work must be done to clean all that stuff.
 
-mod editor;
+mod shader;
 mod vertex;
+mod view;
 
-use crate::{
-  editor::{Editor, ScreenPos, Selection},
-  vertex::Semantics,
-};
-use glfw::{Action, Context as _, Key, MouseButton, WindowEvent};
+use crate::{shader::ShaderInterface, vertex::Semantics, view::EditorView};
+use cgmath::Vector2;
+use glfw::{Action, Context as _, Key, Modifiers, MouseButton, WindowEvent};
 use luminance::{
   blending::{Blending, Equation, Factor},
   context::GraphicsContext,
@@ -17,79 +16,227 @@ use luminance::{
 };
 use luminance_glfw::GlfwSurface;
 use luminance_windowing::WindowOpt;
+use spline_editor::{
+  camera::Camera,
+  coords::Viewport,
+  document::Document,
+  export::{
+    export_space, export_spline, ExportFormat, Ramp, Sampling, Table, RAMP_EXTENSION,
+    SPLINE_EXTENSION,
+  },
+  gradient::{Gradient, Palette},
+  inspector::Inspector,
+  interpolation::InterpolationKind,
+  mode::CurveMode,
+  model::{EditorModel, ScreenPos},
+  scalar,
+  space::{self, Projection},
+  tangent::AutoTangent,
+};
 use splines::Interpolation;
+use std::{
+  env,
+  path::{Path, PathBuf},
+  process,
+};
 
-const LINE_VS_SRC: &str = include_str!("vs.glsl");
-const LINE_FS_SRC: &str = include_str!("fs.glsl");
+const LINE_VS_SRC: &str = include_str!("line_vs.glsl");
+const LINE_GS_SRC: &str = include_str!("line_gs.glsl");
+const LINE_FS_SRC: &str = include_str!("line_fs.glsl");
 const POINT_VS_SRC: &str = include_str!("point_vs.glsl");
 const POINT_GS_SRC: &str = include_str!("point_gs.glsl");
 const POINT_FS_SRC: &str = include_str!("point_fs.glsl");
 
+/// Distance, in view space, under which positions snap to other keys.
+const SNAP_DIST: f32 = 0.03;
+
+/// Title of the window, followed by the name of the active channel and the description of the
+/// inspected key, if any.
+const TITLE: &str = "spline editor";
+
+/// Document used when no path is passed on the command line.
+const DEFAULT_DOCUMENT_PATH: &str = "spline.json";
+/// Number of samples of tables exported from the editor.
+const DEFAULT_EXPORT_SAMPLES: usize = 256;
+
+const USAGE: &str = "usage:
+  spline-editor [DOCUMENT]
+  spline-editor export DOCUMENT OUTPUT… [--rate SAMPLES_PER_UNIT | --count SAMPLES] [--channel NAME]
+
+Exported formats are guessed from the output extensions: json for the spline itself, as a
+Spline<f32, f32>, csv, bin, rs and glsl for tables of samples of a function document, or ramp
+for the RGBA8 texture of a gradient document. The first channel is exported unless another one is
+named.";
+
 fn main() {
-  let mut surface =
-    GlfwSurface::new_gl33("spline editor", WindowOpt::default()).expect("create surface");
+  let args: Vec<_> = env::args().skip(1).collect();
+
+  if args.first().map(String::as_str) == Some("export") {
+    if let Err(e) = export(&args[1..]) {
+      eprintln!("{}\n\n{}", e, USAGE);
+      process::exit(1);
+    }
+
+    return;
+  }
+
+  let mut surface = GlfwSurface::new_gl33(TITLE, WindowOpt::default()).expect("create surface");
 
-  let mut editor = Editor::new(&mut surface);
+  let mut editor = EditorModel::new();
+  let mut view = EditorView::new(&mut surface);
+  let mut inspector = Inspector::new();
+  let mut title = TITLE.to_owned();
+  let mut auto_tangent = AutoTangent::Smooth;
 
-  // misc
-  let mut cursor_pos: Option<[f32; 2]> = None;
-  let mut cursor_pressed_pos: Option<[f32; 2]> = None;
+  // the document is the first argument, if any; load it if it already exists
+  let document_path = args
+    .first()
+    .map(PathBuf::from)
+    .unwrap_or_else(|| PathBuf::from(DEFAULT_DOCUMENT_PATH));
+
+  if document_path.exists() {
+    match editor.load(&document_path) {
+      Ok(()) => println!("loaded {}", document_path.display()),
+      Err(e) => eprintln!("cannot load {}: {}", document_path.display(), e),
+    }
+  }
+
+  let (w, h) = surface.window.get_size();
+  let (fb_w, fb_h) = surface.window.get_framebuffer_size();
+  let mut viewport = Viewport::new([w as u32, h as u32], [fb_w as u32, fb_h as u32]);
+
+  let mut camera = Camera::default();
+  camera.frame(
+    ScreenPos::new(0., -1.),
+    ScreenPos::new(1., 1.),
+    viewport.aspect_ratio(),
+  );
+
+  // misc; cursor positions are expressed in view space
+  let mut cursor_pos: Option<ScreenPos> = None;
+  let mut cursor_pressed_pos: Option<ScreenPos> = None;
   let mut mouse_left_pressed = false;
+  let mut mouse_middle_pressed = false;
 
   let mut point_program = surface
-    .new_shader_program::<Semantics, (), ()>()
+    .new_shader_program::<Semantics, (), ShaderInterface>()
     .from_strings(POINT_VS_SRC, None, POINT_GS_SRC, POINT_FS_SRC)
     .expect("shader program")
     .ignore_warnings();
 
   let mut line_program = surface
-    .new_shader_program::<Semantics, (), ()>()
-    .from_strings(LINE_VS_SRC, None, None, LINE_FS_SRC)
+    .new_shader_program::<Semantics, (), ShaderInterface>()
+    .from_strings(LINE_VS_SRC, None, LINE_GS_SRC, LINE_FS_SRC)
     .expect("shader program")
     .ignore_warnings();
 
   'app: loop {
-    let (fb_width, fb_height) = surface.window.get_framebuffer_size();
-
     // event handling
     surface.window.glfw.poll_events();
     for (_, event) in glfw::flush_messages(&surface.events_rx) {
       match event {
-        WindowEvent::Close | WindowEvent::Key(Key::Escape, _, Action::Release, _) => break 'app,
+        WindowEvent::Close => break 'app,
+
+        // snapping is enabled while a control key is held
+        WindowEvent::Key(Key::LeftControl, _, action, _)
+        | WindowEvent::Key(Key::RightControl, _, action, _) => {
+          editor.snapping_mut().enabled = action != Action::Release;
+        }
+
+        // dragged keys leave their handles behind while an alt key is held
+        WindowEvent::Key(Key::LeftAlt, _, action, _)
+        | WindowEvent::Key(Key::RightAlt, _, action, _) => {
+          editor.set_keep_handles(action != Action::Release);
+        }
+
+        // while typing in the inspector, the keyboard is used for text entry only
+        WindowEvent::Char(c) if inspector.is_editing() => inspector.input(c),
+
+        WindowEvent::Key(key, _, action, _) if inspector.is_editing() => {
+          let result = match (key, action) {
+            (Key::Enter, Action::Press) | (Key::KpEnter, Action::Press) => {
+              inspector.commit(&mut editor)
+            }
+
+            (Key::Tab, Action::Press) => inspector.next_field(&mut editor),
+
+            (Key::Backspace, Action::Press) | (Key::Backspace, Action::Repeat) => {
+              inspector.erase();
+              Ok(())
+            }
+
+            // on release, so that the release doesn’t quit the application once typing stops
+            (Key::Escape, Action::Release) => {
+              inspector.cancel();
+              Ok(())
+            }
+
+            _ => Ok(()),
+          };
+
+          if let Err(e) = result {
+            eprintln!("{}", e);
+          }
+        }
+
+        WindowEvent::Key(Key::Enter, _, Action::Press, _)
+        | WindowEvent::Key(Key::KpEnter, _, Action::Press, _) => {
+          inspector.begin(&editor);
+        }
+
+        WindowEvent::Key(Key::Escape, _, Action::Release, _) => break 'app,
+
+        WindowEvent::Size(w, h) => {
+          viewport.resize_window([w as u32, h as u32]);
+        }
 
         WindowEvent::FramebufferSize(w, h) => {
-          println!("new framebuffer dimensions: {}×{}", w, h);
+          viewport.resize_framebuffer([w as u32, h as u32]);
         }
 
         WindowEvent::CursorPos(x, y) => {
-          let xy = [
-            x as f32 / fb_width as f32,
-            1. - 2. * y as f32 / fb_height as f32,
-          ];
-          cursor_pos = Some(xy);
+          let view_pos = viewport.window_to_view(ScreenPos::new(x as f32, y as f32));
 
-          if mouse_left_pressed {
-            if let Some(selection) = *editor.selection() {
-              let p = ScreenPos::new(xy[0], xy[1]);
+          if mouse_middle_pressed {
+            if let Some(prev) = cursor_pos {
+              camera.pan(view_pos - prev);
+            }
+          }
 
-              match selection {
-                Selection::Key(i) => {
-                  editor.move_key(i, p).unwrap();
-                }
+          // the orbit view turns while the left button is held
+          if let (true, Some(Projection::Orbit(mut orbit)), Some(prev)) =
+            (mouse_left_pressed, editor.projection(), cursor_pos)
+          {
+            orbit.rotate(view_pos - prev);
+            editor.set_projection(Some(Projection::Orbit(orbit)));
+          }
 
-                Selection::Handle(i, h) => {
-                  editor.move_handle(i, p, h).unwrap();
-                }
+          cursor_pos = Some(view_pos);
+
+          if mouse_left_pressed {
+            let p = camera.from_view(view_pos);
+
+            if editor.is_dragging() {
+              if let Err(e) = editor.drag_to(p) {
+                eprintln!("cannot move: {}", e);
               }
+            } else {
+              editor.update_marquee(p);
             }
           }
         }
 
-        WindowEvent::MouseButton(MouseButton::Button1, Action::Release, _) => {
-          if !editor.is_selecting() {
-            if let Some([x, y]) = cursor_pos {
-              if cursor_pressed_pos == cursor_pos {
-                editor.add_point(ScreenPos::new(x, y), Interpolation::Cosine);
+        WindowEvent::MouseButton(MouseButton::Button1, Action::Release, mods) => {
+          if editor.is_dragging() {
+            // a whole drag is a single history entry
+            editor.end_drag();
+          } else if editor.marquee().is_some() {
+            editor.end_marquee(mods.contains(Modifiers::Shift));
+
+            // a simple click in the void adds a point
+            if let Some(view_pos) = cursor_pos {
+              if cursor_pressed_pos == cursor_pos && !editor.is_selecting() {
+                editor.add_point(camera.from_view(view_pos), Interpolation::Cosine);
               }
             }
           }
@@ -98,13 +245,67 @@ fn main() {
           cursor_pressed_pos = None;
         }
 
-        WindowEvent::MouseButton(MouseButton::Button1, Action::Press, _) => {
+        WindowEvent::MouseButton(MouseButton::Button1, Action::Press, mods) => {
+          // in gradient mode, clicking a swatch of the palette colors the selected keys
+          let swatch = cursor_pos
+            .filter(|_| editor.mode() == CurveMode::Gradient)
+            .and_then(|view_pos| Palette::new(viewport.aspect_ratio()).pick(view_pos));
+
+          if let Some(rgb) = swatch {
+            if !editor.is_selecting() {
+              println!("select keys to color them");
+            } else if let Err(e) = editor.pick_color(rgb) {
+              eprintln!("cannot set color: {}", e);
+            }
+
+            continue;
+          }
+
           mouse_left_pressed = true;
           cursor_pressed_pos = cursor_pos;
 
+          // nothing can be picked in the orbit view, which turns instead
+          if let Some(Projection::Orbit(_)) = editor.projection() {
+            continue;
+          }
+
           // try to select something at the current cursor, if any
-          if let Some([x, y]) = cursor_pos {
-            let _ = editor.select(ScreenPos::new(x, y));
+          if let Some(view_pos) = cursor_pos {
+            let p = camera.from_view(view_pos);
+            let picked = if mods.contains(Modifiers::Shift) {
+              editor.toggle_select(p, &camera)
+            } else {
+              editor.select(p, &camera)
+            };
+
+            match picked {
+              // grab the selection, unless the picked content was just removed from it
+              Some(grabbed) if editor.is_selected(grabbed) => editor.begin_drag(grabbed, p),
+              Some(_) => (),
+              None => editor.begin_marquee(p),
+            }
+          }
+        }
+
+        WindowEvent::MouseButton(MouseButton::Button3, action, _) => {
+          mouse_middle_pressed = action != Action::Release;
+        }
+
+        WindowEvent::Scroll(_, steps) => {
+          if let Some(view_pos) = cursor_pos {
+            camera.zoom(steps as f32, view_pos);
+          }
+        }
+
+        WindowEvent::Key(Key::Home, _, Action::Release, _) => {
+          if let Some((min, max)) = editor.bounds() {
+            camera.frame(min, max, viewport.aspect_ratio());
+          }
+        }
+
+        WindowEvent::Key(Key::F, _, Action::Release, _) => {
+          if let Some((min, max)) = editor.selection_bounds().or_else(|| editor.bounds()) {
+            camera.frame(min, max, viewport.aspect_ratio());
           }
         }
 
@@ -112,15 +313,241 @@ fn main() {
           editor.deselect();
         }
 
+        // import a 3D spline exported next to the document into the x, y and z channels
+        WindowEvent::Key(Key::I, _, Action::Release, mods)
+          if mods.contains(Modifiers::Control) && editor.mode() == CurveMode::Space =>
+        {
+          let path = document_path.with_extension(format!("path.{}", SPLINE_EXTENSION));
+
+          match space::load(&path).map(|spline| editor.import_space(&spline)) {
+            Ok(Ok(())) => println!("imported {}", path.display()),
+            Ok(Err(e)) => eprintln!("cannot import {}: {}", path.display(), e),
+            Err(e) => eprintln!("cannot import {}: {}", path.display(), e),
+          }
+        }
+
+        // import a scalar spline exported next to the document as a new channel
+        WindowEvent::Key(Key::I, _, Action::Release, mods) if mods.contains(Modifiers::Control) => {
+          let path = document_path.with_extension(format!("curve.{}", SPLINE_EXTENSION));
+
+          match scalar::load(&path) {
+            Ok(spline) => {
+              editor.import_scalar(format!("channel {}", editor.channels().len() + 1), &spline);
+              println!("imported {}", path.display());
+              print_channels(&editor);
+            }
+
+            Err(e) => eprintln!("cannot import {}: {}", path.display(), e),
+          }
+        }
+
+        // insert a key on the curve at the time under the cursor
+        WindowEvent::Key(Key::I, _, Action::Release, _) => {
+          let t =
+            cursor_pos.and_then(|view_pos| editor.time_at(camera.from_view(view_pos), &camera));
+
+          if let Some(t) = t {
+            if let Err(e) = editor.insert_key(t) {
+              eprintln!("cannot insert key: {}", e);
+            }
+          }
+        }
+
+        // cycle through the curve modes; gradients might add component channels
+        WindowEvent::Key(Key::M, _, Action::Release, _) => {
+          editor.set_mode(editor.mode().next());
+          println!("{} mode", editor.mode().name());
+          print_channels(&editor);
+        }
+
+        // show 3D paths from the top, the front, the side or orbiting around them
+        WindowEvent::Key(Key::P, _, Action::Release, _) if editor.mode() == CurveMode::Space => {
+          let projection = Projection::cycle(editor.projection(), &editor.space());
+          editor.set_projection(projection);
+          println!("{} view", projection.map_or("curves", Projection::name));
+
+          if let Some((min, max)) = editor.bounds() {
+            camera.frame(min, max, viewport.aspect_ratio());
+          }
+        }
+
+        // time the keys of a path by its length
+        WindowEvent::Key(Key::R, _, Action::Release, _) => {
+          if let Err(e) = editor.time_by_arc_length() {
+            eprintln!("cannot retime keys: {}", e);
+          }
+        }
+
+        // remove the active channel
+        WindowEvent::Key(Key::Backspace, _, Action::Release, mods)
+          if mods.contains(Modifiers::Control) =>
+        {
+          if let Ok(channel) = editor.remove_channel(editor.active_channel()) {
+            println!("removed channel {}", channel.name());
+            print_channels(&editor);
+          }
+        }
+
         WindowEvent::Key(Key::Backspace, _, Action::Release, _) => {
-          if let Some(i) = editor.selected_point() {
-            let _ = editor.remove_point(i);
+          editor.remove_selected();
+        }
+
+        // switch to the next channel; shift goes back
+        WindowEvent::Key(Key::Tab, _, Action::Release, mods) => {
+          let n = editor.channels().len();
+          let i = if mods.contains(Modifiers::Shift) {
+            (editor.active_channel() + n - 1) % n
+          } else {
+            (editor.active_channel() + 1) % n
+          };
+
+          if editor.set_active_channel(i).is_ok() {
+            print_channels(&editor);
+          }
+        }
+
+        WindowEvent::Key(Key::N, _, Action::Release, _) => {
+          let name = format!("channel {}", editor.channels().len() + 1);
+          editor.add_channel(name);
+          print_channels(&editor);
+        }
+
+        // show or hide the active channel
+        WindowEvent::Key(Key::H, _, Action::Release, _) => {
+          let i = editor.active_channel();
+          let visible = !editor.channels()[i].is_visible();
+
+          if editor.set_channel_visible(i, visible).is_ok() {
+            print_channels(&editor);
+          }
+        }
+
+        // lock or unlock the active channel
+        WindowEvent::Key(Key::L, _, Action::Release, _) => {
+          let i = editor.active_channel();
+          let locked = !editor.channels()[i].is_locked();
+
+          if editor.set_channel_locked(i, locked).is_ok() {
+            print_channels(&editor);
           }
         }
 
         WindowEvent::Key(Key::Space, _, Action::Release, _) => {
-          if let Some(i) = editor.selected_point() {
-            let _ = editor.toggle_interpolation(i);
+          editor.toggle_interpolation();
+        }
+
+        // cycle the tangent mode of the selected keys
+        WindowEvent::Key(Key::T, _, Action::Release, _) => {
+          if let Some(&i) = editor.selected_keys().first() {
+            let mode = editor.tangent_mode(i).unwrap_or_default().next();
+            println!("switching tangent mode to {}", mode.name());
+            editor.set_tangent_mode(mode);
+          }
+        }
+
+        // compute the tangents of the selected keys; shift picks another scheme
+        WindowEvent::Key(Key::K, _, Action::Release, mods) => {
+          if mods.contains(Modifiers::Shift) {
+            auto_tangent = auto_tangent.next();
+            println!("tangent scheme: {}", auto_tangent.name());
+          } else {
+            editor.compute_tangents(auto_tangent);
+          }
+        }
+
+        // number keys pick an interpolation mode directly
+        WindowEvent::Key(key, _, Action::Release, _) if interpolation_key(key).is_some() => {
+          if let Some(kind) = interpolation_key(key) {
+            println!("switching interpolation to {}", kind.name());
+            editor.set_interpolation(kind);
+          }
+        }
+
+        WindowEvent::Key(Key::A, _, Action::Release, mods) if mods.contains(Modifiers::Control) => {
+          editor.select_all();
+        }
+
+        WindowEvent::Key(Key::G, _, Action::Release, _) => {
+          let snapping = editor.snapping_mut();
+          snapping.target = snapping.target.next();
+          println!("snapping to {:?}", snapping.target);
+        }
+
+        WindowEvent::Key(Key::Z, _, Action::Release, mods) if mods.contains(Modifiers::Control) => {
+          if mods.contains(Modifiers::Shift) {
+            editor.redo();
+          } else {
+            editor.undo();
+          }
+        }
+
+        WindowEvent::Key(Key::S, _, Action::Release, mods) if mods.contains(Modifiers::Control) => {
+          match editor.save(&document_path) {
+            Ok(()) => println!("saved {}", document_path.display()),
+            Err(e) => eprintln!("cannot save {}: {}", document_path.display(), e),
+          }
+        }
+
+        // export the active channel in all formats next to the document
+        WindowEvent::Key(Key::E, _, Action::Release, mods) if mods.contains(Modifiers::Control) => {
+          if editor.mode().is_graph() {
+            let path = document_path.with_extension(format!("curve.{}", SPLINE_EXTENSION));
+
+            match export_spline(editor.spline(), &path) {
+              Ok(()) => println!("exported {}", path.display()),
+              Err(e) => eprintln!("cannot export {}: {}", path.display(), e),
+            }
+          }
+
+          // and the whole 3D path as a Spline<f32, Vector3<f32>>
+          if editor.mode() == CurveMode::Space {
+            let path = document_path.with_extension(format!("path.{}", SPLINE_EXTENSION));
+
+            match export_space(&editor.space(), &path) {
+              Ok(()) => println!("exported {}", path.display()),
+              Err(e) => eprintln!("cannot export {}: {}", path.display(), e),
+            }
+          }
+
+          // and the whole gradient as a ramp texture
+          if editor.mode() == CurveMode::Gradient {
+            let path = document_path.with_extension(RAMP_EXTENSION);
+            let sampling = Sampling::Count(DEFAULT_EXPORT_SAMPLES);
+
+            match Ramp::bake(&editor.gradient(), sampling).and_then(|ramp| ramp.export(&path)) {
+              Ok(()) => println!("exported {}", path.display()),
+              Err(e) => eprintln!("cannot export {}: {}", path.display(), e),
+            }
+          }
+
+          // tables hold a single value per sample, which is all of a function curve only
+          if editor.mode() != CurveMode::Function {
+            println!(
+              "tables are only exported in {} mode",
+              CurveMode::Function.name()
+            );
+          } else {
+            match Table::bake(editor.spline(), Sampling::Count(DEFAULT_EXPORT_SAMPLES)) {
+              Ok(table) => {
+                for format in &ExportFormat::ALL {
+                  let path = document_path.with_extension(format.extension());
+
+                  match table.export(&path) {
+                    Ok(()) => println!("exported {}", path.display()),
+                    Err(e) => eprintln!("cannot export {}: {}", path.display(), e),
+                  }
+                }
+              }
+
+              Err(e) => eprintln!("cannot export: {}", e),
+            }
+          }
+        }
+
+        WindowEvent::Key(Key::O, _, Action::Release, mods) if mods.contains(Modifiers::Control) => {
+          match editor.load(&document_path) {
+            Ok(()) => println!("loaded {}", document_path.display()),
+            Err(e) => eprintln!("cannot load {}: {}", document_path.display(), e),
           }
         }
 
@@ -128,7 +555,25 @@ fn main() {
       }
     }
 
-    editor.rebuild_tess_if_needed(&mut surface).unwrap();
+    // keep the snapping distance constant on screen
+    editor.snapping_mut().tolerance =
+      Vector2::new(SNAP_DIST / camera.scale.x, SNAP_DIST / camera.scale.y);
+
+    // show the active channel and the inspected key in the title bar
+    let channel = &editor.channels()[editor.active_channel()];
+    let new_title = match inspector.describe(&editor) {
+      Some(desc) => format!("{} — {} — {}", TITLE, channel.name(), desc),
+      None => format!("{} — {}", TITLE, channel.name()),
+    };
+
+    if new_title != title {
+      surface.window.set_title(&new_title);
+      title = new_title;
+    }
+
+    view
+      .update_if_needed(&mut surface, &editor, &camera, &viewport)
+      .unwrap();
 
     // render
     let back_buffer = surface.back_buffer().unwrap();
@@ -144,17 +589,49 @@ fn main() {
     let render = surface
       .new_pipeline_gate()
       .pipeline(&back_buffer, &pipeline_state, |_, mut shd_gate| {
-        // lines
-        shd_gate.shade(&mut line_program, |_, _, mut rdr_gate| {
+        // grid and lines
+        shd_gate.shade(&mut line_program, |mut iface, uni, mut rdr_gate| {
+          iface.set(&uni.view_center, camera.center.into());
+          iface.set(&uni.view_scale, camera.scale.into());
+          iface.set(&uni.aspect_ratio, viewport.aspect_ratio());
+          iface.set(&uni.pixel_size, viewport.pixel_size());
+
           rdr_gate.render(&render_state, |mut tess_gate| {
-            tess_gate.render(editor.lines())
+            tess_gate.render(view.grid())?;
+
+            if let Some(gradient) = view.gradient() {
+              tess_gate.render(gradient)?;
+            }
+
+            // projected 3D paths replace the curves
+            if editor.projection().is_none() {
+              tess_gate.render(view.lines())
+            } else if let Some(lines) = view.projected_lines() {
+              tess_gate.render(lines)
+            } else {
+              Ok(())
+            }
           })
         })?;
 
         // points
-        shd_gate.shade(&mut point_program, |_, _, mut rdr_gate| {
+        shd_gate.shade(&mut point_program, |mut iface, uni, mut rdr_gate| {
+          iface.set(&uni.view_center, camera.center.into());
+          iface.set(&uni.view_scale, camera.scale.into());
+          iface.set(&uni.aspect_ratio, viewport.aspect_ratio());
+
           rdr_gate.render(&render_state, |mut tess_gate| {
-            tess_gate.render(editor.points())
+            if let Some(palette) = view.palette() {
+              tess_gate.render(palette)?;
+            }
+
+            if editor.projection().is_none() {
+              tess_gate.render(view.points())
+            } else if let Some(points) = view.projected_points() {
+              tess_gate.render(points)
+            } else {
+              Ok(())
+            }
           })
         })
       })
@@ -167,3 +644,154 @@ fn main() {
     }
   }
 }
+
+/// Export tables from a document without opening any window.
+///
+/// `args` are the command line arguments following `export`.
+fn export(args: &[String]) -> Result<(), String> {
+  let mut paths = Vec::new();
+  let mut sampling = Sampling::Count(DEFAULT_EXPORT_SAMPLES);
+  let mut channel = None;
+  let mut args = args.iter();
+
+  while let Some(arg) = args.next() {
+    match arg.as_str() {
+      "--channel" => {
+        channel = Some(
+          args
+            .next()
+            .ok_or_else(|| format!("missing value for {}", arg))?,
+        );
+      }
+
+      "--rate" | "--count" => {
+        let value = args
+          .next()
+          .ok_or_else(|| format!("missing value for {}", arg))?;
+
+        sampling = if arg == "--rate" {
+          Sampling::Rate(
+            value
+              .parse()
+              .map_err(|_| format!("invalid rate: {}", value))?,
+          )
+        } else {
+          Sampling::Count(
+            value
+              .parse()
+              .map_err(|_| format!("invalid count: {}", value))?,
+          )
+        };
+      }
+
+      path => paths.push(PathBuf::from(path)),
+    }
+  }
+
+  if paths.len() < 2 {
+    return Err("missing document or output".to_owned());
+  }
+
+  let doc =
+    Document::load(&paths[0]).map_err(|e| format!("cannot load {}: {}", paths[0].display(), e))?;
+  let channel = match channel {
+    Some(name) => doc
+      .channel(name)
+      .ok_or_else(|| format!("no channel named {}", name))?,
+    None => doc
+      .channels
+      .first()
+      .ok_or_else(|| "the document has no channel".to_owned())?,
+  };
+  let outputs = &paths[1..];
+
+  if !doc.mode.is_graph() && outputs.iter().any(|path| is_spline_path(path)) {
+    return Err("only function curves can be exported as scalar splines".to_owned());
+  }
+
+  if doc.mode != CurveMode::Gradient && outputs.iter().any(|path| is_ramp_path(path)) {
+    return Err("only gradients can be exported as ramps".to_owned());
+  }
+
+  let is_table_path = |path: &Path| !is_spline_path(path) && !is_ramp_path(path);
+
+  // the other coordinates of paths would be lost in tables
+  if doc.mode != CurveMode::Function && outputs.iter().any(|path| is_table_path(path)) {
+    return Err("only function curves can be exported as tables".to_owned());
+  }
+
+  // tables and ramps are only baked if needed, as splines that cannot be sampled can still be
+  // exported
+  let table = if outputs.iter().any(|path| is_table_path(path)) {
+    Some(Table::bake(&channel.spline, sampling).map_err(|e| e.to_string())?)
+  } else {
+    None
+  };
+  let ramp = if outputs.iter().any(|path| is_ramp_path(path)) {
+    let gradient = Gradient::new(
+      doc
+        .channels
+        .iter()
+        .map(|channel| (channel.name.as_str(), &channel.spline)),
+    );
+    Some(Ramp::bake(&gradient, sampling).map_err(|e| e.to_string())?)
+  } else {
+    None
+  };
+
+  for path in outputs {
+    match (&table, &ramp) {
+      (Some(table), _) if is_table_path(path) => table.export(path),
+      (_, Some(ramp)) if is_ramp_path(path) => ramp.export(path),
+      _ => export_spline(&channel.spline, path),
+    }
+    .map_err(|e| format!("cannot export {}: {}", path.display(), e))?;
+    println!("exported {}", path.display());
+  }
+
+  Ok(())
+}
+
+/// Check whether an output path is meant for the spline itself rather than a table.
+fn is_spline_path(path: &Path) -> bool {
+  path.extension().and_then(|ext| ext.to_str()) == Some(SPLINE_EXTENSION)
+}
+
+/// Check whether an output path is meant for the ramp texture of a gradient.
+fn is_ramp_path(path: &Path) -> bool {
+  path.extension().and_then(|ext| ext.to_str()) == Some(RAMP_EXTENSION)
+}
+
+/// Print the list of channels, marking the active one.
+fn print_channels(editor: &EditorModel) {
+  for (i, channel) in editor.channels().iter().enumerate() {
+    let active = if i == editor.active_channel() {
+      '>'
+    } else {
+      ' '
+    };
+    let hidden = if channel.is_visible() {
+      ""
+    } else {
+      " (hidden)"
+    };
+    let locked = if channel.is_locked() { " (locked)" } else { "" };
+
+    println!("{} {}{}{}", active, channel.name(), hidden, locked);
+  }
+}
+
+/// Interpolation mode picked by a number key, in the cycling order.
+fn interpolation_key(key: Key) -> Option<InterpolationKind> {
+  let i = match key {
+    Key::Num1 | Key::Kp1 => 0,
+    Key::Num2 | Key::Kp2 => 1,
+    Key::Num3 | Key::Kp3 => 2,
+    Key::Num4 | Key::Kp4 => 3,
+    Key::Num5 | Key::Kp5 => 4,
+    Key::Num6 | Key::Kp6 => 5,
+    _ => return None,
+  };
+
+  InterpolationKind::ALL.get(i).copied()
+}
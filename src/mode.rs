@@ -0,0 +1,57 @@
+//! Curve modes.
+//!
+//! Keys hold a time and a 2D value, which can be read in two ways. In [`CurveMode::Function`],
+//! curves are graphs of functions of time, as animation curves are: the horizontal coordinate of
+//! a key is its time and only the vertical one is a value. In [`CurveMode::Path`], values are 2D
+//! points and curves are the paths they follow, times being assigned independently — by hand or
+//! from the length of the path. [`CurveMode::Gradient`] reads function curves as the components
+//! of a color ramp; see [`crate::gradient`]. [`CurveMode::Space`] reads them as the coordinates of
+//! a 3D path; see [`crate::space`].
+
+use serde::{Deserialize, Serialize};
+
+/// How the keys of a document are read.
+#[derive(Clone, Copy, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CurveMode {
+  /// Curves are graphs of functions of time; the horizontal coordinate of keys is their time.
+  #[default]
+  Function,
+  /// Curves are 2D paths; times are independent of the values.
+  Path,
+  /// Curves are graphs of the red, green, blue and alpha components of a color gradient.
+  Gradient,
+  /// Curves are graphs of the x, y and z coordinates of a 3D path.
+  Space,
+}
+
+impl CurveMode {
+  /// All the modes, in cycling order.
+  pub const ALL: [CurveMode; 4] = [
+    CurveMode::Function,
+    CurveMode::Path,
+    CurveMode::Gradient,
+    CurveMode::Space,
+  ];
+
+  /// Next mode in cycling order.
+  pub fn next(self) -> Self {
+    let i = Self::ALL.iter().position(|&mode| mode == self).unwrap_or(0);
+    Self::ALL[(i + 1) % Self::ALL.len()]
+  }
+
+  /// Short, human-readable name.
+  pub fn name(self) -> &'static str {
+    match self {
+      CurveMode::Function => "function",
+      CurveMode::Path => "path",
+      CurveMode::Gradient => "gradient",
+      CurveMode::Space => "3d",
+    }
+  }
+
+  /// Whether curves are graphs of functions of time, keys sitting at their time.
+  pub fn is_graph(self) -> bool {
+    self != CurveMode::Path
+  }
+}
@@ -0,0 +1,2364 @@
+//! Editing model.
+//!
+//! The model holds the edited channels, the selection and the undo / redo history, and exposes all
+//! the editing operations. It doesn’t know anything about rendering: views watch
+//! [`EditorModel::revision`] to know when they must rebuild their own representation.
+//!
+//! A document holds several curves side by side — position.x, position.y, rotation… — each one in
+//! its own [`Channel`]. Picking and editing always target the active channel; the other ones are
+//! only displayed. All the channels of a document share the same [`CurveMode`], which tells how
+//! times relate to values.
+
+use cgmath::{InnerSpace as _, Vector2, Vector3};
+use splines::{Interpolation, Key, Spline};
+use std::{cmp::Ordering, fmt, mem, path::Path};
+
+use crate::{
+  camera::Camera,
+  document::{ChannelDocument, Document, DocumentError, LEGACY_CHANNEL_NAME},
+  flatten::{is_bezier, segment_point},
+  gradient::{self, Gradient, Rgba},
+  grid::Grid,
+  history::History,
+  interpolation::{self, InterpolationKind},
+  mode::CurveMode,
+  scalar,
+  snap::Snapping,
+  space::{self, Projection, SpaceCurve},
+  tangent::{self, AutoTangent, TangentMode},
+};
+
+const POINT_SELECTION_DIST: f32 = 0.08;
+const HISTORY_DEPTH: usize = 256;
+/// Samples per segment when measuring or searching the path in [`CurveMode::Path`].
+const ARC_LENGTH_SAMPLES: usize = 32;
+/// Largest time difference between a key and a value set at its time, for the key to be reused.
+const KEY_TIME_TOLERANCE: f32 = 1e-4;
+
+/// Colors given to new channels, in turn.
+pub const CHANNEL_COLORS: [[f32; 3]; 6] = [
+  [0.5, 0.5, 1.],
+  [1., 0.6, 0.3],
+  [0.4, 0.9, 0.5],
+  [0.9, 0.4, 0.8],
+  [0.3, 0.85, 0.9],
+  [0.95, 0.9, 0.4],
+];
+
+/// Position on screen.
+pub type ScreenPos = Vector2<f32>;
+
+/// State stored in the history: all the channels, the index of the active one and the mode.
+type Snapshot = (Vec<Channel>, usize, CurveMode);
+
+/// Stable identifier of a key.
+///
+/// Key indices change whenever keys are added, removed or moved past each other; identifiers
+/// don’t, and survive undo and redo. They are not saved in documents.
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct KeyId(u64);
+
+/// A curve of the document, edited alongside the others.
+#[derive(Clone, Debug)]
+pub struct Channel {
+  // Name shown to the user.
+  name: String,
+  // Color the curve is drawn with.
+  color: [f32; 3],
+  // Whether the curve is drawn; hidden channels cannot be picked.
+  visible: bool,
+  // Whether the curve is protected against edits.
+  locked: bool,
+  // The actual spline the user is editing.
+  spline: Spline<f32, ScreenPos>,
+  // Tangent mode of each key.
+  tangents: Vec<TangentMode>,
+  // Identifier of each key.
+  ids: Vec<KeyId>,
+}
+
+impl Channel {
+  /// Create an empty, visible and unlocked channel.
+  fn new(name: String, color: [f32; 3]) -> Self {
+    Channel {
+      name,
+      color,
+      visible: true,
+      locked: false,
+      spline: Spline::from_vec(Vec::new()),
+      tangents: Vec::new(),
+      ids: Vec::new(),
+    }
+  }
+
+  /// Name of the channel.
+  pub fn name(&self) -> &str {
+    &self.name
+  }
+
+  /// Color the curve is drawn with.
+  pub fn color(&self) -> [f32; 3] {
+    self.color
+  }
+
+  /// Whether the curve is drawn.
+  pub fn is_visible(&self) -> bool {
+    self.visible
+  }
+
+  /// Whether the curve is protected against edits.
+  pub fn is_locked(&self) -> bool {
+    self.locked
+  }
+
+  /// Whether the curve can be edited: it must be visible and unlocked.
+  pub fn is_editable(&self) -> bool {
+    self.visible && !self.locked
+  }
+
+  /// The spline of the channel.
+  pub fn spline(&self) -> &Spline<f32, ScreenPos> {
+    &self.spline
+  }
+
+  /// Tangent mode of a key, if it exists.
+  pub fn tangent_mode(&self, index: usize) -> Option<TangentMode> {
+    self.tangents.get(index).copied()
+  }
+}
+
+impl PartialEq for Channel {
+  fn eq(&self, other: &Self) -> bool {
+    self.name == other.name
+      && self.color == other.color
+      && self.visible == other.visible
+      && self.locked == other.locked
+      && self.spline.keys() == other.spline.keys()
+      && self.tangents == other.tangents
+      && self.ids == other.ids
+  }
+}
+
+/// Editor model.
+#[derive(Debug)]
+pub struct EditorModel {
+  // All the channels of the document; there’s always at least one.
+  channels: Vec<Channel>,
+  // Index of the channel that picking and editing target.
+  active: usize,
+  // How times relate to values.
+  mode: CurveMode,
+  // Identifier given to the next new key, unique across channels.
+  next_id: u64,
+  // Undo / redo history of the channels.
+  history: History<Snapshot>,
+  // Currently selected content; each element appears at most once.
+  selection: Vec<Selection>,
+  // Mouse drag in progress, if any.
+  drag: Option<Drag>,
+  // Rubber-band rectangle being drawn, if any, as its two opposite corners.
+  marquee: Option<(ScreenPos, ScreenPos)>,
+  // Background grid.
+  grid: Grid,
+  // Snapping settings.
+  snapping: Snapping,
+  // Whether dragged keys leave their handles where they are.
+  keep_handles: bool,
+  // How 3D paths are shown, if not as component curves.
+  projection: Option<Projection>,
+  // Bumped every time something visible changes.
+  revision: u64,
+}
+
+impl Default for EditorModel {
+  fn default() -> Self {
+    Self::new()
+  }
+}
+
+impl EditorModel {
+  /// Create an empty model, with a single empty channel.
+  pub fn new() -> Self {
+    let channels = vec![Channel::new(
+      LEGACY_CHANNEL_NAME.to_owned(),
+      CHANNEL_COLORS[0],
+    )];
+    let active = 0;
+    let mode = CurveMode::default();
+    let next_id = 0;
+    let history = History::new(HISTORY_DEPTH);
+    let selection = Vec::new();
+    let drag = None;
+    let marquee = None;
+    let grid = Grid::default();
+    let snapping = Snapping::default();
+    let keep_handles = false;
+    let projection = None;
+    let revision = 0;
+
+    EditorModel {
+      channels,
+      active,
+      mode,
+      next_id,
+      history,
+      selection,
+      drag,
+      marquee,
+      grid,
+      snapping,
+      keep_handles,
+      projection,
+      revision,
+    }
+  }
+
+  /// The spline being edited: the one of the active channel.
+  pub fn spline(&self) -> &Spline<f32, ScreenPos> {
+    &self.channel().spline
+  }
+
+  /// All the channels, in order.
+  pub fn channels(&self) -> &[Channel] {
+    &self.channels
+  }
+
+  /// Index of the active channel, which picking and editing target.
+  pub fn active_channel(&self) -> usize {
+    self.active
+  }
+
+  /// The active channel.
+  fn channel(&self) -> &Channel {
+    &self.channels[self.active]
+  }
+
+  /// The active channel, mutably.
+  fn channel_mut(&mut self) -> &mut Channel {
+    &mut self.channels[self.active]
+  }
+
+  /// Check that the active channel can be edited.
+  fn check_editable(&self) -> Result<(), EditorError> {
+    if self.channel().is_editable() {
+      Ok(())
+    } else {
+      Err(EditorError::ReadOnlyChannel(self.active))
+    }
+  }
+
+  /// Make another channel active. The selection is cleared, as it belongs to the previously active
+  /// channel.
+  pub fn set_active_channel(&mut self, index: usize) -> Result<(), EditorError> {
+    if index >= self.channels.len() {
+      return Err(EditorError::UnknownChannel(index));
+    }
+
+    if index != self.active {
+      self.end_drag();
+      self.active = index;
+      self.selection.clear();
+      self.marquee = None;
+      self.touch();
+    }
+
+    Ok(())
+  }
+
+  /// Add an empty channel after the others and make it active. Its index is returned.
+  pub fn add_channel(&mut self, name: impl Into<String>) -> usize {
+    let color = CHANNEL_COLORS[self.channels.len() % CHANNEL_COLORS.len()];
+    self.push_channel(Channel::new(name.into(), color))
+  }
+
+  /// Add a channel editing a scalar spline after the others and make it active. Its index is
+  /// returned.
+  ///
+  /// The spline is shown as a function curve; see [`scalar::from_scalar`].
+  pub fn import_scalar(&mut self, name: impl Into<String>, spline: &Spline<f32, f32>) -> usize {
+    let color = CHANNEL_COLORS[self.channels.len() % CHANNEL_COLORS.len()];
+    let mut channel = Channel::new(name.into(), color);
+    channel.spline = scalar::from_scalar(spline);
+    channel.tangents = vec![TangentMode::default(); spline.len()];
+    channel.ids = (0..spline.len()).map(|_| self.new_id()).collect();
+
+    self.push_channel(channel)
+  }
+
+  /// Add a channel after the others and make it active. Its index is returned.
+  fn push_channel(&mut self, channel: Channel) -> usize {
+    let index = self.record(|model| {
+      model.channels.push(channel);
+      model.channels.len() - 1
+    });
+
+    // cannot fail, the channel was just added
+    let _ = self.set_active_channel(index);
+    index
+  }
+
+  /// Remove a channel. The removed channel is returned.
+  ///
+  /// Removing the only channel leaves an empty one in its place, as there must always be one.
+  pub fn remove_channel(&mut self, index: usize) -> Result<Channel, EditorError> {
+    if index >= self.channels.len() {
+      return Err(EditorError::UnknownChannel(index));
+    }
+
+    self.end_drag();
+
+    let removed = self.record(|model| {
+      let removed = model.channels.remove(index);
+
+      if model.channels.is_empty() {
+        let name = LEGACY_CHANNEL_NAME.to_owned();
+        model.channels.push(Channel::new(name, CHANNEL_COLORS[0]));
+      }
+
+      if index < model.active || model.active >= model.channels.len() {
+        model.active = model.active.saturating_sub(1);
+      }
+
+      model.selection.clear();
+      model.marquee = None;
+      model.touch();
+      removed
+    });
+
+    Ok(removed)
+  }
+
+  /// Rename a channel.
+  pub fn rename_channel(
+    &mut self,
+    index: usize,
+    name: impl Into<String>,
+  ) -> Result<(), EditorError> {
+    let name = name.into();
+    self.update_channel(index, |channel| channel.name = name)
+  }
+
+  /// Change the color a channel is drawn with.
+  pub fn set_channel_color(&mut self, index: usize, color: [f32; 3]) -> Result<(), EditorError> {
+    self.update_channel(index, |channel| channel.color = color)
+  }
+
+  /// Show or hide a channel. Hiding the active channel clears the selection, as hidden keys cannot
+  /// be picked.
+  pub fn set_channel_visible(&mut self, index: usize, visible: bool) -> Result<(), EditorError> {
+    self.update_channel(index, |channel| channel.visible = visible)?;
+
+    if !visible && index == self.active {
+      self.end_drag();
+      self.selection.clear();
+    }
+
+    Ok(())
+  }
+
+  /// Lock or unlock a channel. Locked channels can be picked but not edited.
+  pub fn set_channel_locked(&mut self, index: usize, locked: bool) -> Result<(), EditorError> {
+    if locked && index == self.active {
+      self.end_drag();
+    }
+
+    self.update_channel(index, |channel| channel.locked = locked)
+  }
+
+  /// Change the properties of a channel, as a single transaction.
+  fn update_channel(
+    &mut self,
+    index: usize,
+    f: impl FnOnce(&mut Channel),
+  ) -> Result<(), EditorError> {
+    if index >= self.channels.len() {
+      return Err(EditorError::UnknownChannel(index));
+    }
+
+    self.record(|model| {
+      f(&mut model.channels[index]);
+      model.touch();
+    });
+
+    Ok(())
+  }
+
+  /// Revision of the model.
+  ///
+  /// The revision changes every time the channels or the selection change, so that views can
+  /// cheaply know whether they are out of date.
+  pub fn revision(&self) -> u64 {
+    self.revision
+  }
+
+  /// Background grid.
+  pub fn grid(&self) -> &Grid {
+    &self.grid
+  }
+
+  /// Change the background grid.
+  pub fn set_grid(&mut self, grid: Grid) {
+    self.grid = grid;
+    self.touch();
+  }
+
+  /// Snapping settings.
+  pub fn snapping(&self) -> &Snapping {
+    &self.snapping
+  }
+
+  /// Mutable snapping settings.
+  pub fn snapping_mut(&mut self) -> &mut Snapping {
+    &mut self.snapping
+  }
+
+  /// Whether dragged keys leave their handles where they are.
+  pub fn keep_handles(&self) -> bool {
+    self.keep_handles
+  }
+
+  /// Set whether dragged keys leave their handles where they are, instead of carrying them.
+  pub fn set_keep_handles(&mut self, keep_handles: bool) {
+    self.keep_handles = keep_handles;
+  }
+
+  /// How times relate to values.
+  pub fn mode(&self) -> CurveMode {
+    self.mode
+  }
+
+  /// Switch to another mode, for all the channels.
+  ///
+  /// Switching to [`CurveMode::Function`] moves every key horizontally to its time, carrying its
+  /// handles along, and pins the handles to their time in the graph — see
+  /// [`scalar::pin_handles`]; switching to [`CurveMode::Path`] doesn’t change any key. Switching
+  /// to [`CurveMode::Gradient`] or [`CurveMode::Space`] does the same as switching to
+  /// [`CurveMode::Function`], and adds the missing component channels; in [`CurveMode::Space`],
+  /// the x channel becomes active unless a component channel already is.
+  pub fn set_mode(&mut self, mode: CurveMode) {
+    if mode == self.mode {
+      return;
+    }
+
+    self.end_drag();
+    self.record(|model| {
+      if mode.is_graph() {
+        for channel in &mut model.channels {
+          let mut keys = channel.spline.keys().to_vec();
+
+          for key in &mut keys {
+            let delta = ScreenPos::new(key.t - key.value.x, 0.);
+            key.value += delta;
+            carry_handles(key, delta);
+          }
+
+          scalar::pin_handles(&mut keys);
+          channel.spline = Spline::from_vec(keys);
+        }
+      }
+
+      match mode {
+        CurveMode::Gradient => model.add_component_channels(&gradient::COMPONENTS),
+
+        CurveMode::Space => {
+          model.add_component_channels(&space::COMPONENTS);
+
+          // keys are picked in the active channel, which must be part of the path
+          if space::component_index(&model.channel().name).is_none() {
+            model.active = model
+              .channels
+              .iter()
+              .position(|channel| channel.name == space::COMPONENTS[0].0)
+              .unwrap();
+            model.selection.clear();
+          }
+        }
+
+        _ => (),
+      }
+
+      model.mode = mode;
+      model.touch();
+    })
+  }
+
+  /// Projection 3D paths are shown, picked and edited through, if any. This is always `None`
+  /// outside of [`CurveMode::Space`], where curves are edited directly.
+  pub fn projection(&self) -> Option<Projection> {
+    self.projection.filter(|_| self.mode == CurveMode::Space)
+  }
+
+  /// Show 3D paths through a projection, or as component curves if `None`.
+  ///
+  /// This is a viewing setting: it’s not part of the history.
+  pub fn set_projection(&mut self, projection: Option<Projection>) {
+    self.end_drag();
+    self.projection = projection;
+    self.marquee = None;
+    self.touch();
+  }
+
+  /// Snap a position according to the snapping settings, ignoring some keys.
+  ///
+  /// Keys of the active channel are not snapped to through a projection, as they are not where
+  /// the curve is drawn.
+  fn snap(&self, p: ScreenPos, ignored: &[usize]) -> ScreenPos {
+    let keys = if self.projection().is_some() {
+      &[]
+    } else {
+      self.channel().spline.keys()
+    };
+
+    self.snapping.apply(p, &self.grid, keys, ignored)
+  }
+
+  /// Mark the model as changed.
+  fn touch(&mut self) {
+    self.revision = self.revision.wrapping_add(1);
+  }
+
+  /// Load a document from a file, replacing the channels being edited. The first channel becomes
+  /// active.
+  ///
+  /// The handles of function curves are moved to the time they stand for, as this editor places
+  /// them; see [`scalar::place_handles`]. Curves keep their shape, and documents saved by this
+  /// editor are loaded unchanged, but other documents are not saved back identically.
+  pub fn load(&mut self, path: impl AsRef<Path>) -> Result<(), EditorError> {
+    let doc = Document::load(path).map_err(EditorError::DocumentError)?;
+    let mut channels = Vec::with_capacity(doc.channels.len());
+
+    for mut channel in doc.channels {
+      // handles of function curves might not have been placed by this editor
+      if doc.mode.is_graph() {
+        let mut keys = channel.spline.keys().to_vec();
+        scalar::place_handles(&mut keys);
+        channel.spline = Spline::from_vec(keys);
+      }
+
+      let mut tangents = channel.tangents;
+      tangents.resize(channel.spline.len(), TangentMode::default());
+      let ids = (0..channel.spline.len()).map(|_| self.new_id()).collect();
+
+      channels.push(Channel {
+        name: channel.name,
+        color: channel.color,
+        visible: channel.visible,
+        locked: channel.locked,
+        spline: channel.spline,
+        tangents,
+        ids,
+      });
+    }
+
+    if channels.is_empty() {
+      let name = LEGACY_CHANNEL_NAME.to_owned();
+      channels.push(Channel::new(name, CHANNEL_COLORS[0]));
+    }
+
+    self.channels = channels;
+    self.active = 0;
+    self.mode = doc.mode;
+    self.history.clear();
+    self.selection.clear();
+    self.drag = None;
+    self.marquee = None;
+    self.touch();
+
+    Ok(())
+  }
+
+  /// Save the channels being edited into a document file.
+  pub fn save(&self, path: impl AsRef<Path>) -> Result<(), EditorError> {
+    let channels = self
+      .channels
+      .iter()
+      .map(|channel| ChannelDocument {
+        name: channel.name.clone(),
+        color: channel.color,
+        visible: channel.visible,
+        locked: channel.locked,
+        spline: channel.spline.clone(),
+        tangents: channel.tangents.clone(),
+      })
+      .collect();
+
+    Document::new(self.mode, channels)
+      .save(path)
+      .map_err(EditorError::DocumentError)
+  }
+
+  /// Begin a transaction.
+  ///
+  /// All the mutations performed until [`EditorModel::commit_transaction`] is called are coalesced
+  /// into a single history entry. This is typically used for mouse drags.
+  pub fn begin_transaction(&mut self) {
+    self.history.begin(self.snapshot());
+  }
+
+  /// Commit the current transaction, if any.
+  pub fn commit_transaction(&mut self) {
+    self.history.commit(&self.snapshot());
+  }
+
+  /// Current state of the channels, as stored in the history.
+  fn snapshot(&self) -> Snapshot {
+    (self.channels.clone(), self.active, self.mode)
+  }
+
+  /// Run a mutation, recording it as its own transaction unless one is already open.
+  fn record<F, R>(&mut self, f: F) -> R
+  where
+    F: FnOnce(&mut Self) -> R,
+  {
+    let standalone = !self.history.is_recording();
+
+    if standalone {
+      self.begin_transaction();
+    }
+
+    let r = f(self);
+
+    if standalone {
+      self.commit_transaction();
+    }
+
+    r
+  }
+
+  /// Undo the last transaction. Return whether anything was undone.
+  pub fn undo(&mut self) -> bool {
+    self.commit_transaction();
+
+    match self.history.undo(self.snapshot()) {
+      Some(snapshot) => {
+        self.restore(snapshot);
+        true
+      }
+
+      None => false,
+    }
+  }
+
+  /// Redo the last undone transaction. Return whether anything was redone.
+  pub fn redo(&mut self) -> bool {
+    self.commit_transaction();
+
+    match self.history.redo(self.snapshot()) {
+      Some(snapshot) => {
+        self.restore(snapshot);
+        true
+      }
+
+      None => false,
+    }
+  }
+
+  /// Restore the channels from a snapshot.
+  ///
+  /// The selection is kept for the keys that still exist in the active channel.
+  fn restore(&mut self, (channels, active, mode): Snapshot) {
+    let selected = self.selected_ids();
+
+    self.channels = channels;
+    self.active = active;
+    self.mode = mode;
+    self.reselect(selected);
+    self.drag = None;
+    self.touch();
+  }
+
+  /// Current selection, along with the identifiers of the keys it belongs to.
+  fn selected_ids(&self) -> Vec<(Selection, KeyId)> {
+    self
+      .selection
+      .iter()
+      .map(|&selection| (selection, self.channel().ids[selection.key()]))
+      .collect()
+  }
+
+  /// Select again what was selected when [`EditorModel::selected_ids`] was called, for the keys
+  /// that still exist in the active channel.
+  fn reselect(&mut self, selected: Vec<(Selection, KeyId)>) {
+    self.selection = selected
+      .into_iter()
+      .filter_map(|(mut selection, id)| {
+        let index = self.key_index(id)?;
+        selection.remap(|_| index);
+        Some(selection)
+      })
+      .collect();
+  }
+
+  /// Take the selection and the drag out of the model, along with the identifiers of the keys of
+  /// the active channel, so that other channels can be made active and edited in the meantime.
+  fn detach_selection(&mut self) -> DetachedSelection {
+    DetachedSelection {
+      active: self.active,
+      ids: self.channel().ids.clone(),
+      selection: mem::take(&mut self.selection),
+      drag: self.drag.take(),
+    }
+  }
+
+  /// Make the channel active again when [`EditorModel::detach_selection`] was called, and give its
+  /// selection and drag back, for the keys that still exist.
+  fn attach_selection(&mut self, detached: DetachedSelection) {
+    let DetachedSelection {
+      active,
+      ids,
+      selection,
+      drag,
+    } = detached;
+    self.active = active;
+
+    let index = |i: usize| self.key_index(ids[i]);
+    let remap = |mut selection: Selection| {
+      let i = index(selection.key())?;
+      selection.remap(|_| i);
+      Some(selection)
+    };
+
+    let selection = selection.into_iter().filter_map(remap).collect();
+    let drag = drag.and_then(|drag| {
+      Some(Drag {
+        grabbed: remap(drag.grabbed)?,
+        origins: drag
+          .origins
+          .into_iter()
+          .filter_map(|(i, p)| Some((index(i)?, p)))
+          .collect(),
+        ..drag
+      })
+    });
+
+    self.selection = selection;
+    self.drag = drag;
+  }
+
+  /// Give an identifier to a new key.
+  fn new_id(&mut self) -> KeyId {
+    self.next_id += 1;
+    KeyId(self.next_id)
+  }
+
+  /// Identifier of a key of the active channel, if it exists.
+  pub fn key_id(&self, index: usize) -> Option<KeyId> {
+    self.channel().ids.get(index).copied()
+  }
+
+  /// Current index of a key in the active channel, if it still exists there.
+  pub fn key_index(&self, id: KeyId) -> Option<usize> {
+    self.channel().ids.iter().position(|&i| i == id)
+  }
+
+  /// Move a point.
+  ///
+  /// Moving a key might change its index if it passes over one of its neighbours; the selection is
+  /// updated accordingly.
+  pub fn move_key(&mut self, index: usize, p: ScreenPos) -> Result<(), EditorError> {
+    let p = self.snap(p, &[index]);
+    self.move_keys(&[(index, p)])
+  }
+
+  /// Move several points at once. Their handles, if any, move along.
+  pub fn move_keys(&mut self, moves: &[(usize, ScreenPos)]) -> Result<(), EditorError> {
+    self.move_keys_with(moves, true)
+  }
+
+  /// Move several points at once, carrying their handles along or leaving them where they are.
+  fn move_keys_with(
+    &mut self,
+    moves: &[(usize, ScreenPos)],
+    carry_handles: bool,
+  ) -> Result<(), EditorError> {
+    if let Some(&(index, _)) = moves
+      .iter()
+      .find(|(i, _)| *i >= self.channel().spline.len())
+    {
+      return Err(EditorError::UnknownKey(index));
+    }
+
+    self.check_editable()?;
+
+    self.record(|model| {
+      let mut keys = model.channel().spline.keys().to_vec();
+
+      for &(index, p) in moves {
+        let key = &mut keys[index];
+        let delta = p - key.value;
+
+        // move along interpolation handles if any
+        if carry_handles {
+          self::carry_handles(key, delta);
+        }
+
+        // in path mode, times don’t depend on positions
+        let t = match model.mode {
+          CurveMode::Function | CurveMode::Gradient | CurveMode::Space => p.x,
+          CurveMode::Path => key.t,
+        };
+        *key = Key::new(t, p, key.interpolation);
+
+        // keep the handles consistent with the tangent mode; the output handle wins
+        tangent::enforce(
+          &mut keys,
+          index,
+          model.channel().tangents[index],
+          HandleSelection::Mirror,
+        );
+      }
+
+      model.reorder_keys(keys);
+
+      // function handles left behind keep their value, but follow the key in time
+      if !carry_handles && model.mode.is_graph() {
+        let mut keys = model.channel().spline.keys().to_vec();
+        scalar::place_handles(&mut keys);
+        model.channel_mut().spline = Spline::from_vec(keys);
+      }
+
+      model.update_handles();
+      model.touch();
+
+      Ok(())
+    })
+  }
+
+  /// Change the time of a key.
+  ///
+  /// In [`CurveMode::Function`], this moves the key horizontally. In [`CurveMode::Path`], the key
+  /// stays where it is; it might change index if its time passes over the one of a neighbour.
+  pub fn set_key_time(&mut self, index: usize, t: f32) -> Result<(), EditorError> {
+    let key = *self
+      .channel()
+      .spline
+      .get(index)
+      .ok_or(EditorError::UnknownKey(index))?;
+
+    if self.mode.is_graph() {
+      return self.move_keys(&[(index, ScreenPos::new(t, key.value.y))]);
+    }
+
+    self.check_editable()?;
+
+    self.record(|model| {
+      let mut keys = model.channel().spline.keys().to_vec();
+      keys[index].t = t;
+
+      model.reorder_keys(keys);
+      model.update_handles();
+      model.touch();
+
+      Ok(())
+    })
+  }
+
+  /// Retime the keys of the active channel so that the path is followed at constant speed, keeping
+  /// the times of the first and last keys.
+  ///
+  /// This is only meaningful in [`CurveMode::Path`]; nothing happens in
+  /// [`CurveMode::Function`], where times are positions.
+  pub fn time_by_arc_length(&mut self) -> Result<(), EditorError> {
+    let keys = self.channel().spline.keys();
+
+    if self.mode != CurveMode::Path || keys.len() < 3 {
+      return Ok(());
+    }
+
+    self.check_editable()?;
+
+    // cumulative length of the path at each key
+    let spline = &self.channel().spline;
+    let mut lengths = vec![0.];
+    for i in 0..keys.len() - 1 {
+      let (t0, t1) = (keys[i].t, keys[i + 1].t);
+      let mut prev = keys[i].value;
+      let mut length = 0.;
+
+      for j in 1..=ARC_LENGTH_SAMPLES {
+        let t = t0 + (t1 - t0) * j as f32 / ARC_LENGTH_SAMPLES as f32;
+        let p = segment_point(spline, self.mode, i, t).unwrap_or(keys[i + 1].value);
+        length += (p - prev).magnitude();
+        prev = p;
+      }
+
+      lengths.push(lengths[i] + length);
+    }
+
+    let total = lengths[lengths.len() - 1];
+    if total <= 0. {
+      return Ok(());
+    }
+
+    let (first, last) = (keys[0].t, keys[keys.len() - 1].t);
+    let mut keys = keys.to_vec();
+    for (key, length) in keys.iter_mut().zip(lengths) {
+      key.t = first + (last - first) * length / total;
+    }
+
+    self.record(|model| {
+      model.channel_mut().spline = Spline::from_vec(keys);
+      model.update_handles();
+      model.touch();
+
+      Ok(())
+    })
+  }
+
+  /// Time of the active channel’s curve at a position, if any.
+  ///
+  /// In [`CurveMode::Function`], this is the horizontal coordinate of `p`. In [`CurveMode::Path`],
+  /// this is the time of the point of the path closest to `p` in view space.
+  pub fn time_at(&self, p: ScreenPos, camera: &Camera) -> Option<f32> {
+    if let Some(projection) = self.projection() {
+      return self.projected_time_at(projection, p, camera);
+    }
+
+    if self.mode.is_graph() {
+      return Some(p.x);
+    }
+
+    let spline = &self.channel().spline;
+    let keys = spline.keys();
+    let target = camera.to_view(p);
+    let mut closest: Option<(f32, f32)> = None;
+
+    for i in 0..keys.len().saturating_sub(1) {
+      let (t0, t1) = (keys[i].t, keys[i + 1].t);
+
+      for j in 0..=ARC_LENGTH_SAMPLES {
+        let t = t0 + (t1 - t0) * j as f32 / ARC_LENGTH_SAMPLES as f32;
+
+        if let Some(q) = segment_point(spline, self.mode, i, t) {
+          let dist = (camera.to_view(q) - target).magnitude();
+
+          if closest.is_none_or(|(_, closest)| dist < closest) {
+            closest = Some((t, dist));
+          }
+        }
+      }
+    }
+
+    closest.map(|(t, _)| t)
+  }
+
+  /// Time of the point of the projected 3D path closest to `p`, if any.
+  fn projected_time_at(
+    &self,
+    projection: Projection,
+    p: ScreenPos,
+    camera: &Camera,
+  ) -> Option<f32> {
+    let space = self.space();
+    let times = space.key_times();
+    let target = camera.to_view(p);
+    let mut closest: Option<(f32, f32)> = None;
+
+    for w in times.windows(2) {
+      for j in 0..=ARC_LENGTH_SAMPLES {
+        let t = w[0] + (w[1] - w[0]) * j as f32 / ARC_LENGTH_SAMPLES as f32;
+
+        if let Some(q) = space.position(t).and_then(|q| projection.project(q)) {
+          let dist = (camera.to_view(q) - target).magnitude();
+
+          if closest.is_none_or(|(_, closest)| dist < closest) {
+            closest = Some((t, dist));
+          }
+        }
+      }
+    }
+
+    closest.map(|(t, _)| t)
+  }
+
+  /// Replace the keys of the spline with a modified version of them, in which keys might not be
+  /// sorted anymore.
+  ///
+  /// `keys` must have the same length as the current keys; the key at index `i` in `keys` is the
+  /// new version of the key currently at index `i`. The selection and the drag in progress are
+  /// updated to follow the keys as they get sorted.
+  fn reorder_keys(&mut self, keys: Vec<Key<f32, ScreenPos>>) {
+    let mut tagged: Vec<_> = keys.into_iter().enumerate().collect();
+    tagged.sort_by(|(_, a), (_, b)| a.t.partial_cmp(&b.t).unwrap_or(Ordering::Less));
+
+    let mut new_indices = vec![0; tagged.len()];
+    for (new, &(old, _)) in tagged.iter().enumerate() {
+      new_indices[old] = new;
+    }
+
+    for selection in &mut self.selection {
+      selection.remap(|i| new_indices[i]);
+    }
+
+    if let Some(ref mut drag) = self.drag {
+      drag.grabbed.remap(|i| new_indices[i]);
+
+      for (i, _) in &mut drag.origins {
+        *i = new_indices[*i];
+      }
+    }
+
+    let channel = self.channel_mut();
+    channel.tangents = tagged
+      .iter()
+      .map(|&(old, _)| channel.tangents[old])
+      .collect();
+    channel.ids = tagged.iter().map(|&(old, _)| channel.ids[old]).collect();
+    channel.spline = Spline::from_vec(tagged.into_iter().map(|(_, key)| key).collect());
+  }
+
+  /// Recompute the handles depending on the neighbours of their key, as they might have changed:
+  /// all the handles of keys in [`TangentMode::Auto`], and the time of all the handles in
+  /// [`CurveMode::Function`] — see [`scalar::pin_handles`].
+  fn update_handles(&mut self) {
+    let mut keys = self.channel().spline.keys().to_vec();
+    let mut changed = false;
+
+    for (i, &mode) in self.channel().tangents.iter().enumerate() {
+      if mode == TangentMode::Auto {
+        changed |= tangent::enforce(&mut keys, i, mode, HandleSelection::Mirror);
+      }
+    }
+
+    if self.mode.is_graph() {
+      changed |= scalar::pin_handles(&mut keys);
+    }
+
+    if changed {
+      self.channel_mut().spline = Spline::from_vec(keys);
+    }
+  }
+
+  /// Color gradient made of the component channels; see [`gradient`].
+  pub fn gradient(&self) -> Gradient<'_> {
+    Gradient::new(
+      self
+        .channels
+        .iter()
+        .map(|channel| (channel.name.as_str(), &channel.spline)),
+    )
+  }
+
+  /// Set the color of the gradient at time `t`, setting a key at that time in each component
+  /// channel.
+  ///
+  /// Keys already at that time are moved to the new value; other ones are inserted on the curves,
+  /// keeping their shape. Missing component channels are added. The active channel and the
+  /// selection are left untouched.
+  pub fn set_color(&mut self, t: f32, color: Rgba) -> Result<(), EditorError> {
+    self.end_drag();
+    self.set_components(&gradient::COMPONENTS, t, &color.map(|x| x.clamp(0., 1.)))
+  }
+
+  /// Add the component channels that don’t exist yet, without changing the active channel.
+  fn add_component_channels(&mut self, components: &[(&str, [f32; 3])]) {
+    for &(name, color) in components {
+      if !self.channels.iter().any(|channel| channel.name == name) {
+        self.channels.push(Channel::new(name.to_owned(), color));
+      }
+    }
+  }
+
+  /// Set the values of component channels at time `t`, with a key at that time in each of them.
+  /// Missing channels are added; the active channel and the selection are left untouched.
+  ///
+  /// If a component cannot be set, none is and the channels are left as they were.
+  fn set_components(
+    &mut self,
+    components: &[(&str, [f32; 3])],
+    t: f32,
+    values: &[f32],
+  ) -> Result<(), EditorError> {
+    let names: Vec<_> = components.iter().map(|&(name, _)| name).collect();
+
+    if let Some(i) = self
+      .channels
+      .iter()
+      .position(|channel| names.contains(&channel.name.as_str()) && !channel.is_editable())
+    {
+      return Err(EditorError::ReadOnlyChannel(i));
+    }
+
+    self.record(|model| {
+      let before = model.channels.clone();
+      model.add_component_channels(components);
+
+      let detached = model.detach_selection();
+      let mut result = Ok(());
+
+      for (name, &value) in names.iter().zip(values) {
+        // the channel was just added if it didn’t exist
+        model.active = model
+          .channels
+          .iter()
+          .position(|channel| channel.name == *name)
+          .unwrap();
+        result = model.set_component(t, value);
+
+        // components are set all at once or not at all
+        if result.is_err() {
+          model.channels = before;
+          break;
+        }
+      }
+
+      model.attach_selection(detached);
+      model.touch();
+
+      result
+    })
+  }
+
+  /// Set the value of the active channel at time `t` with a key at that time.
+  fn set_component(&mut self, t: f32, value: f32) -> Result<(), EditorError> {
+    if let Some(&x) = [t, value].iter().find(|x| !x.is_finite()) {
+      return Err(EditorError::InvalidValue(x));
+    }
+
+    let p = ScreenPos::new(t, value);
+    let existing = self
+      .channel()
+      .spline
+      .keys()
+      .iter()
+      .position(|key| (key.t - t).abs() <= KEY_TIME_TOLERANCE);
+
+    let index = match existing {
+      Some(index) => index,
+
+      None => match self.split_segment(t) {
+        Ok(index) => index,
+
+        // outside of the curve, which is simply extended
+        Err(EditorError::NoSegment(_)) => {
+          self.insert_point(p, Interpolation::Linear);
+          return Ok(());
+        }
+
+        Err(e) => return Err(e),
+      },
+    };
+
+    self.move_keys_with(&[(index, p)], true)
+  }
+
+  /// Give a color to the gradient at the time of each selected key of the active channel, keeping
+  /// the opacity it has there.
+  pub fn pick_color(&mut self, rgb: [f32; 3]) -> Result<(), EditorError> {
+    let mut times: Vec<_> = self
+      .selected_keys()
+      .into_iter()
+      .map(|i| self.channel().spline.keys()[i].t)
+      .collect();
+    times.dedup();
+
+    self.record(|model| {
+      for t in times {
+        let alpha = model
+          .gradient()
+          .color(t)
+          .map_or(gradient::DEFAULT_COLOR[3], |color| color[3]);
+        let [r, g, b] = rgb;
+        model.set_color(t, [r, g, b, alpha])?;
+      }
+
+      Ok(())
+    })
+  }
+
+  /// 3D path made of the component channels; see [`space`].
+  pub fn space(&self) -> SpaceCurve<'_> {
+    SpaceCurve::new(
+      self
+        .channels
+        .iter()
+        .map(|channel| (channel.name.as_str(), &channel.spline)),
+    )
+  }
+
+  /// Set the position of the 3D path at time `t`, setting a key at that time in each component
+  /// channel.
+  ///
+  /// As with [`EditorModel::set_color`], keys already at that time are moved and other ones are
+  /// inserted on the curves; missing component channels are added, and the active channel and the
+  /// selection are left untouched.
+  pub fn set_position(&mut self, t: f32, p: Vector3<f32>) -> Result<(), EditorError> {
+    self.set_components(&space::COMPONENTS, t, &[p.x, p.y, p.z])
+  }
+
+  /// Edit a 3D spline: its coordinates replace the content of the component channels, which are
+  /// added if needed, and the document switches to [`CurveMode::Space`].
+  pub fn import_space(&mut self, spline: &Spline<f32, Vector3<f32>>) -> Result<(), EditorError> {
+    if let Some(i) = self
+      .channels
+      .iter()
+      .position(|channel| space::component_index(&channel.name).is_some() && !channel.is_editable())
+    {
+      return Err(EditorError::ReadOnlyChannel(i));
+    }
+
+    self.end_drag();
+    self.record(|model| {
+      model.add_component_channels(&space::COMPONENTS);
+
+      for (&(name, _), component) in space::COMPONENTS.iter().zip(&space::components(spline)) {
+        let ids = (0..component.len()).map(|_| model.new_id()).collect();
+        // the channel was just added if it didn’t exist
+        let channel = model
+          .channels
+          .iter_mut()
+          .find(|channel| channel.name == name)
+          .unwrap();
+
+        channel.spline = scalar::from_scalar(component);
+        channel.tangents = vec![TangentMode::default(); component.len()];
+        channel.ids = ids;
+      }
+
+      model.selection.clear();
+      model.set_mode(CurveMode::Space);
+      model.touch();
+    });
+
+    Ok(())
+  }
+
+  /// Position of a key of the active channel through the [projection](EditorModel::projection):
+  /// the projected point of the 3D path at the time of the key.
+  ///
+  /// `None` is returned if there is no projection, if the key doesn’t exist or if its point cannot
+  /// be projected.
+  pub fn projected_key(&self, index: usize) -> Option<ScreenPos> {
+    let key = self.channel().spline.get(index)?;
+    self.projection()?.project(self.space().position(key.t)?)
+  }
+
+  /// Position of a key of the active channel as it is shown, through the projection if any.
+  fn shown_key(&self, index: usize) -> Option<ScreenPos> {
+    if self.projection().is_some() {
+      self.projected_key(index)
+    } else {
+      self.channel().spline.get(index).map(|key| key.value)
+    }
+  }
+
+  /// Move the points of the 3D path at the time of several keys of the active channel, positions
+  /// being in the plane of the [projection](EditorModel::projection). All the components move, and
+  /// the times of the keys are kept.
+  ///
+  /// This is how keys are dragged through a projection; [`EditorModel::move_keys`] still edits the
+  /// values of the active channel. [`EditorError::ProjectionNotEditable`] is returned if there is no
+  /// projection, or if it cannot be edited.
+  pub fn move_projected_keys(&mut self, moves: &[(usize, ScreenPos)]) -> Result<(), EditorError> {
+    let projection = self
+      .projection()
+      .ok_or(EditorError::ProjectionNotEditable)?;
+    let space = self.space();
+    let positions = moves
+      .iter()
+      .map(|&(index, p)| {
+        let key = self
+          .channel()
+          .spline
+          .get(index)
+          .ok_or(EditorError::UnknownKey(index))?;
+        let reference = space.position(key.t).ok_or(EditorError::NoSegment(key.t))?;
+        let q = projection
+          .unproject(p, reference)
+          .ok_or(EditorError::ProjectionNotEditable)?;
+        Ok((key.t, q))
+      })
+      .collect::<Result<Vec<_>, _>>()?;
+
+    self.check_editable()?;
+    self.record(|model| {
+      for (t, q) in positions {
+        model.set_position(t, q)?;
+      }
+
+      Ok(())
+    })
+  }
+
+  /// Tangent mode of a key of the active channel, if it exists.
+  pub fn tangent_mode(&self, index: usize) -> Option<TangentMode> {
+    self.channel().tangent_mode(index)
+  }
+
+  /// Set the tangent mode of the selected keys, constraining their handles right away.
+  ///
+  /// Tangent modes only apply to stroke Bézier keys, so Bézier keys are turned into stroke Bézier
+  /// ones, which doesn’t change their shape. Other keys keep the mode for when they become stroke
+  /// Bézier keys.
+  pub fn set_tangent_mode(&mut self, mode: TangentMode) {
+    let indices = self.selected_keys();
+
+    if indices.is_empty() || !self.channel().is_editable() {
+      return;
+    }
+
+    self.record(|model| {
+      let mut keys = model.channel().spline.keys().to_vec();
+
+      for &i in &indices {
+        if let Interpolation::Bezier(_) = keys[i].interpolation {
+          interpolation::convert(&mut keys, model.mode, i, InterpolationKind::StrokeBezier);
+        }
+
+        model.channel_mut().tangents[i] = mode;
+        tangent::enforce(&mut keys, i, mode, HandleSelection::Mirror);
+      }
+
+      model.channel_mut().spline = Spline::from_vec(keys);
+      model.update_handles();
+      model.touch();
+    })
+  }
+
+  /// Move a handle of a point.
+  pub fn move_handle(
+    &mut self,
+    index: usize,
+    p: ScreenPos,
+    handle_selection: HandleSelection,
+  ) -> Result<(), EditorError> {
+    let p = self.snap(p, &[]);
+    self.set_handle(index, p, handle_selection)
+  }
+
+  /// Place a handle of a point at an exact position, without snapping.
+  pub fn set_handle(
+    &mut self,
+    index: usize,
+    p: ScreenPos,
+    handle_selection: HandleSelection,
+  ) -> Result<(), EditorError> {
+    self.check_editable()?;
+
+    self.record(|model| {
+      let channel = &mut model.channels[model.active];
+      let key = channel
+        .spline
+        .get_mut(index)
+        .ok_or(EditorError::UnknownKey(index))?;
+
+      match *key.interpolation {
+        Interpolation::Bezier(ref mut handle) => {
+          match handle_selection {
+            HandleSelection::Own => {
+              *handle = p;
+            }
+
+            HandleSelection::Mirror => {
+              // recompute the position by symetrically rotate it
+              *handle = 2. * *key.value - p;
+            }
+          }
+
+          model.update_handles();
+          model.touch();
+          Ok(())
+        }
+
+        Interpolation::StrokeBezier(ref mut input, ref mut output) => {
+          match handle_selection {
+            HandleSelection::Own => {
+              *input = p;
+            }
+
+            HandleSelection::Mirror => {
+              *output = p;
+            }
+          }
+
+          // handles placed by hand are not automatic anymore, but keep their tangent smooth
+          let mode = &mut channel.tangents[index];
+          if *mode == TangentMode::Auto {
+            *mode = TangentMode::Aligned;
+          }
+
+          let (i, o) = tangent::constrain(*mode, *key.value, *input, *output, handle_selection);
+          *input = i;
+          *output = o;
+
+          model.update_handles();
+          model.touch();
+          Ok(())
+        }
+
+        _ => Err(EditorError::WrongInterpolationAssumed(index)),
+      }
+    })
+  }
+
+  /// Position of the threshold marker of the step segment starting at a key, if any.
+  ///
+  /// In [`CurveMode::Function`], the marker lies on the jump, halfway between the values of the
+  /// keys around it. In [`CurveMode::Path`], where the jump is a straight line between the keys,
+  /// the marker lies on it as far from the first key as the threshold is from the start of the
+  /// segment.
+  pub fn threshold_marker(&self, index: usize) -> Option<ScreenPos> {
+    let keys = self.channel().spline.keys();
+    let (cp0, cp1) = (keys.get(index)?, keys.get(index + 1)?);
+
+    match cp0.interpolation {
+      Interpolation::Step(threshold) => {
+        let threshold = threshold.clamp(0., 1.);
+
+        match self.mode {
+          CurveMode::Function | CurveMode::Gradient | CurveMode::Space => Some(ScreenPos::new(
+            cp0.t + (cp1.t - cp0.t) * threshold,
+            (cp0.value.y + cp1.value.y) * 0.5,
+          )),
+          CurveMode::Path => Some(cp0.value + (cp1.value - cp0.value) * threshold),
+        }
+      }
+      _ => None,
+    }
+  }
+
+  /// Move the threshold marker of the step segment starting at a key to `p`, or as close to it
+  /// as possible, changing when the jump happens. Nothing happens on function segments without
+  /// duration.
+  pub fn move_threshold(&mut self, index: usize, p: ScreenPos) -> Result<(), EditorError> {
+    let p = self.snap(p, &[]);
+    let keys = self.channel().spline.keys();
+    let (cp0, cp1) = match (keys.get(index), keys.get(index + 1)) {
+      (Some(cp0), Some(cp1)) => (cp0, cp1),
+      (Some(cp0), None) => return Err(EditorError::NoSegment(cp0.t)),
+      _ => return Err(EditorError::UnknownKey(index)),
+    };
+
+    let threshold = match self.mode {
+      CurveMode::Function | CurveMode::Gradient | CurveMode::Space => {
+        let duration = cp1.t - cp0.t;
+
+        // there’s nowhere to move the jump of a segment without duration
+        if duration <= 0. {
+          return Ok(());
+        }
+
+        (p.x - cp0.t) / duration
+      }
+
+      // project onto the jump
+      CurveMode::Path => {
+        let jump = cp1.value - cp0.value;
+        let len2 = jump.x * jump.x + jump.y * jump.y;
+
+        if len2 > 0. {
+          let d = p - cp0.value;
+          (d.x * jump.x + d.y * jump.y) / len2
+        } else {
+          0.5
+        }
+      }
+    };
+
+    self.set_threshold(index, threshold)
+  }
+
+  /// Set the threshold of a step key, as a fraction of its segment, clamped to [0, 1].
+  pub fn set_threshold(&mut self, index: usize, threshold: f32) -> Result<(), EditorError> {
+    self.check_editable()?;
+
+    self.record(|model| {
+      let channel = &mut model.channels[model.active];
+      let key = channel
+        .spline
+        .get_mut(index)
+        .ok_or(EditorError::UnknownKey(index))?;
+
+      match *key.interpolation {
+        Interpolation::Step(ref mut t) => {
+          *t = threshold.clamp(0., 1.);
+          model.touch();
+          Ok(())
+        }
+
+        _ => Err(EditorError::WrongInterpolationAssumed(index)),
+      }
+    })
+  }
+
+  /// Add a new point.
+  ///
+  /// In [`CurveMode::Function`], the point is inserted at its time, `p.x`. In [`CurveMode::Path`],
+  /// it is appended to the path, its time being the one of the last key plus the distance to it.
+  /// With a [projection](EditorModel::projection), `p` is in its plane and the point is appended
+  /// to the 3D path the same way.
+  ///
+  /// Nothing happens if the active channel cannot be edited.
+  pub fn add_point(&mut self, p: ScreenPos, interpolation: Interpolation<f32, ScreenPos>) {
+    if !self.channel().is_editable() {
+      return;
+    }
+
+    let p = self.snap(p, &[]);
+
+    match self.projection() {
+      Some(projection) => self.append_projected(projection, p),
+      None => self.insert_point(p, interpolation),
+    }
+  }
+
+  /// Append a point to the 3D path, given in the plane of a projection. Its time is the one of the
+  /// last key plus the distance to it, and its hidden coordinate the one of the last key.
+  fn append_projected(&mut self, projection: Projection, p: ScreenPos) {
+    let space = self.space();
+    let last = space
+      .key_times()
+      .last()
+      .and_then(|&t| Some((t, space.position(t)?)));
+    let reference = last.map_or(Vector3::new(0., 0., 0.), |(_, q)| q);
+
+    if let Some(q) = projection.unproject(p, reference) {
+      let t = last.map_or(0., |(t, reference)| t + (q - reference).magnitude());
+
+      // nothing happens if a component cannot be edited, as for other points
+      if self.set_position(t, q).is_ok() {
+        self.selection.clear();
+        self.touch();
+      }
+    }
+  }
+
+  /// Add a new point to the active channel, as [`EditorModel::add_point`] does but without
+  /// snapping.
+  fn insert_point(&mut self, p: ScreenPos, interpolation: Interpolation<f32, ScreenPos>) {
+    self.record(|model| {
+      let mut keys = model.channel().spline.keys().to_vec();
+      let (index, t) = match model.mode {
+        CurveMode::Function | CurveMode::Gradient | CurveMode::Space => {
+          (keys.iter().filter(|key| key.t <= p.x).count(), p.x)
+        }
+        CurveMode::Path => (
+          keys.len(),
+          keys
+            .last()
+            .map_or(0., |last| last.t + (p - last.value).magnitude()),
+        ),
+      };
+      keys.insert(index, Key::new(t, p, interpolation));
+
+      model.channel_mut().spline = Spline::from_vec(keys);
+      model
+        .channel_mut()
+        .tangents
+        .insert(index, TangentMode::default());
+      let id = model.new_id();
+      model.channel_mut().ids.insert(index, id);
+      model.selection.clear();
+      model.update_handles();
+      model.touch();
+    })
+  }
+
+  /// Insert a key on the curve at time `t`, keeping the shape of the curve. The index of the new
+  /// key is returned, and it becomes the only selected content.
+  ///
+  /// Bézier segments are split with de Casteljau’s algorithm; the surrounding keys are turned into
+  /// [`Interpolation::StrokeBezier`] where needed so that their other segments don’t change. Step
+  /// thresholds are adjusted so that the jump happens at the same time. Linear segments are
+  /// trivially split. Cosine and Catmull-Rom segments cannot be split exactly: the new key is
+  /// placed on the curve but the shape around it changes slightly.
+  ///
+  /// With a [projection](EditorModel::projection), the key is inserted in all the components of
+  /// the 3D path.
+  pub fn insert_key(&mut self, t: f32) -> Result<usize, EditorError> {
+    if self.projection().is_some() {
+      self.check_editable()?;
+      let p = self.space().position(t).ok_or(EditorError::NoSegment(t))?;
+      self.set_position(t, p)?;
+
+      let index = self
+        .channel()
+        .spline
+        .keys()
+        .iter()
+        .position(|key| (key.t - t).abs() <= KEY_TIME_TOLERANCE)
+        .ok_or(EditorError::NoSegment(t))?;
+      self.selection = vec![Selection::Key(index)];
+      return Ok(index);
+    }
+
+    self.split_segment(t)
+  }
+
+  /// Insert a key in the active channel at time `t`, keeping the shape of its curve; see
+  /// [`EditorModel::insert_key`].
+  fn split_segment(&mut self, t: f32) -> Result<usize, EditorError> {
+    self.check_editable()?;
+
+    let keys = self.channel().spline.keys();
+    let i = keys
+      .windows(2)
+      .position(|w| w[0].t < t && t < w[1].t)
+      .ok_or(EditorError::NoSegment(t))?;
+    let (cp0, cp1) = (keys[i], keys[i + 1]);
+    let s = (t - cp0.t) / (cp1.t - cp0.t);
+    let lerp = |a: ScreenPos, b: ScreenPos, s: f32| a + (b - a) * s;
+
+    let (interpolation0, mut key, interpolation1) = match cp0.interpolation {
+      Interpolation::Step(threshold) if s < threshold => {
+        // the jump happens in the second half; the first half is flat
+        let key = Key::new(
+          t,
+          cp0.value,
+          Interpolation::Step((threshold - s) / (1. - s)),
+        );
+        (Interpolation::Step(1.), key, cp1.interpolation)
+      }
+
+      Interpolation::Step(threshold) => {
+        // the jump happens in the first half; the second half is flat
+        let key = Key::new(t, cp1.value, Interpolation::Step(0.));
+        (Interpolation::Step(threshold / s), key, cp1.interpolation)
+      }
+
+      Interpolation::Bezier(u) | Interpolation::StrokeBezier(_, u) => {
+        let (a, b) = (cp0.value, cp1.value);
+
+        // cubic control points of the segment; quadratic segments are degree-elevated
+        let c2 = match cp1.interpolation {
+          Interpolation::Bezier(v) => Some(2. * b - v),
+          Interpolation::StrokeBezier(v, _) => Some(v),
+          _ => None,
+        };
+        let (c1, c2_or_elevated) = match c2 {
+          Some(c2) => (u, c2),
+          None => (a + (u - a) * (2. / 3.), b + (u - b) * (2. / 3.)),
+        };
+
+        // de Casteljau
+        let q0 = lerp(a, c1, s);
+        let q1 = lerp(c1, c2_or_elevated, s);
+        let q2 = lerp(c2_or_elevated, b, s);
+        let r0 = lerp(q0, q1, s);
+        let r1 = lerp(q1, q2, s);
+        let p = lerp(r0, r1, s);
+
+        // keep the input handle of the first key, as it shapes the previous segment
+        let input0 = match cp0.interpolation {
+          Interpolation::StrokeBezier(input, _) => input,
+          _ => 2. * a - u,
+        };
+
+        let (output, interpolation1) = match cp1.interpolation {
+          Interpolation::Bezier(v) => (r1, Interpolation::StrokeBezier(q2, v)),
+          Interpolation::StrokeBezier(_, v) => (r1, Interpolation::StrokeBezier(q2, v)),
+          // the second half stays quadratic: its control point is on the original control polygon
+          interpolation => (lerp(u, b, s), interpolation),
+        };
+
+        let key = Key::new(t, p, Interpolation::StrokeBezier(r0, output));
+        (Interpolation::StrokeBezier(input0, q0), key, interpolation1)
+      }
+
+      interpolation => {
+        let p = self
+          .channel()
+          .spline
+          .sample(t)
+          .ok_or(EditorError::NoSegment(t))?;
+        let key = Key::new(t, p, interpolation);
+        (interpolation, key, cp1.interpolation)
+      }
+    };
+
+    // function keys sit at their time
+    if self.mode.is_graph() {
+      key.value.x = t;
+    }
+
+    self.record(|model| {
+      let mut keys = model.channel().spline.keys().to_vec();
+      keys[i].interpolation = interpolation0;
+      keys[i + 1].interpolation = interpolation1;
+      keys.insert(i + 1, key);
+
+      // split Bézier segments leave the new key with collinear handles
+      let mode = if is_bezier(&key.interpolation) {
+        TangentMode::Aligned
+      } else {
+        TangentMode::default()
+      };
+
+      model.channel_mut().spline = Spline::from_vec(keys);
+      model.channel_mut().tangents.insert(i + 1, mode);
+      let id = model.new_id();
+      model.channel_mut().ids.insert(i + 1, id);
+      model.update_handles();
+      model.selection = vec![Selection::Key(i + 1)];
+      model.touch();
+    });
+
+    Ok(i + 1)
+  }
+
+  /// Remove all the selected points. The removed keys are returned.
+  pub fn remove_selected(&mut self) -> Vec<Key<f32, ScreenPos>> {
+    let indices = self.selected_keys();
+
+    if indices.is_empty() || !self.channel().is_editable() {
+      return Vec::new();
+    }
+
+    self.record(|model| {
+      // remove from the end so that indices stay valid
+      let mut removed: Vec<_> = indices
+        .into_iter()
+        .rev()
+        .filter_map(|i| {
+          model.channel_mut().tangents.remove(i);
+          model.channel_mut().ids.remove(i);
+          model.channel_mut().spline.remove(i)
+        })
+        .collect();
+      removed.reverse();
+
+      model.update_handles();
+
+      model.selection.clear();
+      model.touch();
+
+      removed
+    })
+  }
+
+  /// Check if there’s a selection.
+  pub fn is_selecting(&self) -> bool {
+    !self.selection.is_empty()
+  }
+
+  /// Indices of the selected points, sorted.
+  pub fn selected_keys(&self) -> Vec<usize> {
+    let mut keys: Vec<_> = self
+      .selection
+      .iter()
+      .filter_map(|s| match *s {
+        Selection::Key(i) => Some(i),
+        _ => None,
+      })
+      .collect();
+    keys.sort_unstable();
+    keys
+  }
+
+  /// Currently selected content.
+  pub fn selection(&self) -> &[Selection] {
+    &self.selection
+  }
+
+  /// Check whether some content is selected.
+  pub fn is_selected(&self, selection: Selection) -> bool {
+    self.selection.contains(&selection)
+  }
+
+  /// Select some content at the given position, replacing the current selection. The selected
+  /// content is returned if any.
+  ///
+  /// If the content is already selected, the selection is left untouched, so that a group of
+  /// selected keys can be grabbed by any of its keys.
+  pub fn select(&mut self, cursor_pos: ScreenPos, camera: &Camera) -> Option<Selection> {
+    let found = self.pick(cursor_pos, camera);
+
+    match found {
+      Some(selection) if self.is_selected(selection) => (),
+
+      Some(selection) => {
+        self.selection = vec![selection];
+      }
+
+      None => self.selection.clear(),
+    }
+
+    self.touch();
+    found
+  }
+
+  /// Add or remove the content at the given position to or from the selection. The toggled
+  /// content is returned if any.
+  pub fn toggle_select(&mut self, cursor_pos: ScreenPos, camera: &Camera) -> Option<Selection> {
+    let found = self.pick(cursor_pos, camera)?;
+
+    if let Some(i) = self.selection.iter().position(|s| *s == found) {
+      self.selection.remove(i);
+    } else {
+      self.selection.push(found);
+    }
+
+    self.touch();
+    Some(found)
+  }
+
+  /// Select all the keys of the active channel, unless it’s hidden.
+  pub fn select_all(&mut self) {
+    let channel = self.channel();
+    let len = if channel.visible {
+      channel.spline.len()
+    } else {
+      0
+    };
+
+    self.selection = (0..len).map(Selection::Key).collect();
+    self.touch();
+  }
+
+  /// Select all the keys of the active channel lying in the rectangle defined by two opposite
+  /// corners, unless it’s hidden. If `additive` is `true`, the keys are added to the current
+  /// selection instead of replacing it.
+  pub fn select_in_rect(&mut self, a: ScreenPos, b: ScreenPos, additive: bool) {
+    let (min, max) = Self::points_bounds(vec![a, b]).unwrap();
+
+    if !additive {
+      self.selection.clear();
+    }
+
+    let channel = &self.channels[self.active];
+    let len = if channel.visible {
+      channel.spline.len()
+    } else {
+      0
+    };
+    let points: Vec<_> = (0..len)
+      .filter_map(|i| Some((i, self.shown_key(i)?)))
+      .collect();
+
+    for (i, p) in points {
+      let inside = p.x >= min.x && p.x <= max.x && p.y >= min.y && p.y <= max.y;
+
+      if inside && !self.selection.contains(&Selection::Key(i)) {
+        self.selection.push(Selection::Key(i));
+      }
+    }
+
+    self.touch();
+  }
+
+  /// Start drawing a rubber-band rectangle at the given position.
+  pub fn begin_marquee(&mut self, p: ScreenPos) {
+    self.marquee = Some((p, p));
+    self.touch();
+  }
+
+  /// Move the free corner of the rubber-band rectangle, if any.
+  pub fn update_marquee(&mut self, p: ScreenPos) {
+    if let Some((_, ref mut corner)) = self.marquee {
+      *corner = p;
+      self.touch();
+    }
+  }
+
+  /// Stop drawing the rubber-band rectangle and select the keys inside it. If `additive` is
+  /// `true`, the keys are added to the current selection.
+  pub fn end_marquee(&mut self, additive: bool) {
+    if let Some((a, b)) = self.marquee.take() {
+      self.select_in_rect(a, b, additive);
+    }
+  }
+
+  /// Rubber-band rectangle being drawn, if any, as two opposite corners.
+  pub fn marquee(&self) -> Option<(ScreenPos, ScreenPos)> {
+    self.marquee
+  }
+
+  /// Start dragging the selection, grabbed by `grabbed` at position `p`.
+  ///
+  /// If a key is grabbed, all the selected keys follow the cursor, keeping their relative offsets,
+  /// and carry their handles along unless [`EditorModel::keep_handles`] is set. If a handle is
+  /// grabbed, only that handle moves. The whole drag is recorded as a single transaction.
+  ///
+  /// Nothing happens if the active channel cannot be edited.
+  pub fn begin_drag(&mut self, grabbed: Selection, p: ScreenPos) {
+    if !self.channel().is_editable() {
+      return;
+    }
+
+    let origins = self
+      .selected_keys()
+      .into_iter()
+      .filter_map(|i| Some((i, self.shown_key(i)?)))
+      .collect();
+
+    self.begin_transaction();
+    self.drag = Some(Drag {
+      grabbed,
+      anchor: p,
+      origins,
+    });
+  }
+
+  /// Move the cursor while dragging.
+  pub fn drag_to(&mut self, p: ScreenPos) -> Result<(), EditorError> {
+    let drag = match self.drag {
+      Some(ref drag) => drag,
+      None => return Ok(()),
+    };
+
+    match drag.grabbed {
+      Selection::Key(grabbed) => {
+        let mut delta = p - drag.anchor;
+
+        // snap the grabbed key; the other ones follow
+        if let Some(&(_, origin)) = drag.origins.iter().find(|(i, _)| *i == grabbed) {
+          let ignored: Vec<_> = drag.origins.iter().map(|(i, _)| *i).collect();
+          delta = self.snap(origin + delta, &ignored) - origin;
+        }
+
+        let moves: Vec<_> = drag
+          .origins
+          .iter()
+          .map(|&(i, origin)| (i, origin + delta))
+          .collect();
+
+        if self.projection().is_some() {
+          self.move_projected_keys(&moves)
+        } else {
+          self.move_keys_with(&moves, !self.keep_handles)
+        }
+      }
+
+      Selection::Handle(i, h) => self.move_handle(i, p, h),
+      Selection::Threshold(i) => self.move_threshold(i, p),
+    }
+  }
+
+  /// Stop dragging, committing the drag into the history.
+  pub fn end_drag(&mut self) {
+    if self.drag.take().is_some() {
+      self.commit_transaction();
+    }
+  }
+
+  /// Check whether a drag is in progress.
+  pub fn is_dragging(&self) -> bool {
+    self.drag.is_some()
+  }
+
+  /// Find the content at the given position, if any.
+  ///
+  /// Distances are measured once mapped by `camera`, so that the selection tolerance doesn’t
+  /// depend on the zoom level.
+  pub fn pick(&self, cursor_pos: ScreenPos, camera: &Camera) -> Option<Selection> {
+    // only the active channel can be picked, and only if it’s shown
+    if !self.channel().visible {
+      return None;
+    }
+
+    // projected paths only show their keys
+    if self.projection().is_some() {
+      let target = camera.to_view(cursor_pos);
+
+      return (0..self.channel().spline.len())
+        .filter_map(|i| {
+          let dist = (camera.to_view(self.projected_key(i)?) - target).magnitude();
+          Some((i, dist))
+        })
+        .filter(|&(_, dist)| dist <= POINT_SELECTION_DIST)
+        .min_by(|a, b| a.1.total_cmp(&b.1))
+        .map(|(i, _)| Selection::Key(i));
+    }
+
+    let [x, y]: [f32; 2] = camera.to_view(cursor_pos).into();
+    let mut found = None;
+
+    // we want to select a point; check if any is nearby
+    for (i, p) in self.channel().spline.keys().iter().enumerate() {
+      let [px, py]: [f32; 2] = camera.to_view(p.value).into();
+      let dist = ((x - px).powf(2.) + (y - py).powf(2.)).sqrt();
+
+      if dist <= POINT_SELECTION_DIST {
+        // try to select a key first
+        match found {
+          Some((_, prev_dist)) if dist < prev_dist => {
+            found = Some((Selection::Key(i), dist));
+          }
+
+          None => {
+            found = Some((Selection::Key(i), dist));
+          }
+
+          _ => (),
+        }
+      } else {
+        match p.interpolation {
+          Interpolation::Bezier(handle) => {
+            // try to select a handle
+            let [px, py]: [f32; 2] = camera.to_view(handle).into();
+            let dist = ((x - px).powf(2.) + (y - py).powf(2.)).sqrt();
+
+            if dist <= POINT_SELECTION_DIST {
+              match found {
+                Some((_, prev_dist)) if dist < prev_dist => {
+                  found = Some((Selection::Handle(i, HandleSelection::Own), dist));
+                }
+
+                None => {
+                  found = Some((Selection::Handle(i, HandleSelection::Own), dist));
+                }
+
+                _ => (),
+              }
+            }
+
+            let [px, py]: [f32; 2] = camera.to_view(2. * p.value - handle).into();
+            let dist = ((x - px).powf(2.) + (y - py).powf(2.)).sqrt();
+
+            if dist <= POINT_SELECTION_DIST {
+              match found {
+                Some((_, prev_dist)) if dist < prev_dist => {
+                  found = Some((Selection::Handle(i, HandleSelection::Mirror), dist));
+                }
+
+                None => {
+                  found = Some((Selection::Handle(i, HandleSelection::Mirror), dist));
+                }
+
+                _ => (),
+              }
+            }
+          }
+
+          Interpolation::StrokeBezier(input, output) => {
+            // try to select the input handle
+            let [px, py]: [f32; 2] = camera.to_view(input).into();
+            let dist = ((x - px).powf(2.) + (y - py).powf(2.)).sqrt();
+
+            if dist <= POINT_SELECTION_DIST {
+              match found {
+                Some((_, prev_dist)) if dist < prev_dist => {
+                  found = Some((Selection::Handle(i, HandleSelection::Own), dist));
+                }
+
+                None => {
+                  found = Some((Selection::Handle(i, HandleSelection::Own), dist));
+                }
+
+                _ => (),
+              }
+            }
+
+            let [px, py]: [f32; 2] = camera.to_view(output).into();
+            let dist = ((x - px).powf(2.) + (y - py).powf(2.)).sqrt();
+
+            if dist <= POINT_SELECTION_DIST {
+              match found {
+                Some((_, prev_dist)) if dist < prev_dist => {
+                  found = Some((Selection::Handle(i, HandleSelection::Mirror), dist));
+                }
+
+                None => {
+                  found = Some((Selection::Handle(i, HandleSelection::Mirror), dist));
+                }
+
+                _ => (),
+              }
+            }
+          }
+
+          _ => (),
+        }
+      }
+
+      // try to select a threshold marker
+      if let Some(marker) = self.threshold_marker(i) {
+        let [px, py]: [f32; 2] = camera.to_view(marker).into();
+        let dist = ((x - px).powf(2.) + (y - py).powf(2.)).sqrt();
+
+        if dist <= POINT_SELECTION_DIST {
+          match found {
+            Some((_, prev_dist)) if dist < prev_dist => {
+              found = Some((Selection::Threshold(i), dist));
+            }
+
+            None => {
+              found = Some((Selection::Threshold(i), dist));
+            }
+
+            _ => (),
+          }
+        }
+      }
+    }
+
+    found.map(|(selection, _)| selection)
+  }
+
+  /// Smallest area containing all the keys of the visible channels and their handles, if any.
+  ///
+  /// With a projection, this is the area containing the projected keys of the 3D path instead.
+  pub fn bounds(&self) -> Option<(ScreenPos, ScreenPos)> {
+    if let Some(projection) = self.projection() {
+      let space = self.space();
+      let points = space
+        .key_times()
+        .into_iter()
+        .filter_map(|t| projection.project(space.position(t)?));
+
+      return Self::points_bounds(points);
+    }
+
+    let keys = self
+      .channels
+      .iter()
+      .filter(|channel| channel.visible)
+      .flat_map(|channel| channel.spline.keys());
+
+    Self::points_bounds(keys.flat_map(Self::key_points))
+  }
+
+  /// Smallest area containing the selected content, if any.
+  pub fn selection_bounds(&self) -> Option<(ScreenPos, ScreenPos)> {
+    if self.projection().is_some() {
+      let points = self
+        .selected_keys()
+        .into_iter()
+        .filter_map(|i| self.projected_key(i));
+
+      return Self::points_bounds(points);
+    }
+
+    let keys = self.channel().spline.keys();
+    let points = self.selection.iter().flat_map(|s| match *s {
+      Selection::Key(i) | Selection::Handle(i, _) => Self::key_points(&keys[i]),
+      Selection::Threshold(i) => self.threshold_marker(i).into_iter().collect(),
+    });
+
+    Self::points_bounds(points)
+  }
+
+  /// All the points defining a key: its value and its handles, if any.
+  fn key_points(key: &Key<f32, ScreenPos>) -> Vec<ScreenPos> {
+    match key.interpolation {
+      Interpolation::Bezier(u) => vec![key.value, u, 2. * key.value - u],
+      Interpolation::StrokeBezier(input, output) => vec![key.value, input, output],
+      _ => vec![key.value],
+    }
+  }
+
+  /// Smallest area containing a set of points, if not empty.
+  fn points_bounds(points: impl IntoIterator<Item = ScreenPos>) -> Option<(ScreenPos, ScreenPos)> {
+    points.into_iter().fold(None, |bounds, p| match bounds {
+      None => Some((p, p)),
+      Some((min, max)) => Some((
+        ScreenPos::new(p.x.min(min.x), p.y.min(min.y)),
+        ScreenPos::new(p.x.max(max.x), p.y.max(max.y)),
+      )),
+    })
+  }
+
+  /// Deselect, if anything was selected.
+  pub fn deselect(&mut self) {
+    self.selection.clear();
+    self.touch();
+  }
+
+  /// Switch the selected keys to another interpolation mode.
+  ///
+  /// The curve keeps its shape as much as possible: new Bézier handles follow the tangent the curve
+  /// had, and keys already using that mode keep their parameters. See
+  /// [`interpolation::convert`](crate::interpolation::convert) for details.
+  pub fn set_interpolation(&mut self, kind: InterpolationKind) {
+    let indices = self.selected_keys();
+
+    if indices.is_empty() || !self.channel().is_editable() {
+      return;
+    }
+
+    self.record(|model| {
+      let mut keys = model.channel().spline.keys().to_vec();
+
+      for &i in &indices {
+        interpolation::convert(&mut keys, model.mode, i, kind);
+        tangent::enforce(
+          &mut keys,
+          i,
+          model.channel().tangents[i],
+          HandleSelection::Mirror,
+        );
+      }
+
+      model.channel_mut().spline = Spline::from_vec(keys);
+
+      // handles and markers that don’t exist anymore cannot stay selected
+      let keys = model.channels[model.active].spline.keys();
+      model.selection.retain(|selection| match *selection {
+        Selection::Handle(i, _) => keys.get(i).is_some_and(|key| is_bezier(&key.interpolation)),
+        Selection::Threshold(i) => keys
+          .get(i)
+          .is_some_and(|key| matches!(key.interpolation, Interpolation::Step(_))),
+        Selection::Key(_) => true,
+      });
+
+      model.update_handles();
+      model.touch();
+    })
+  }
+
+  /// Compute the handles of the selected keys from their neighbours, with a tangent scheme.
+  ///
+  /// The keys become stroke Bézier keys with [`TangentMode::Aligned`] handles, so that the result
+  /// can be tweaked by hand afterwards.
+  pub fn compute_tangents(&mut self, scheme: AutoTangent) {
+    let indices = self.selected_keys();
+
+    if indices.is_empty() || !self.channel().is_editable() {
+      return;
+    }
+
+    self.record(|model| {
+      let mut keys = model.channel().spline.keys().to_vec();
+
+      // all the keys must be Bézier keys first, as handles depend on whether segments are cubic
+      for &i in &indices {
+        if !matches!(keys[i].interpolation, Interpolation::StrokeBezier(..)) {
+          keys[i].interpolation = Interpolation::StrokeBezier(keys[i].value, keys[i].value);
+        }
+      }
+
+      let slopes = tangent::slopes(&keys, scheme);
+      let clamped = scheme != AutoTangent::Smooth;
+
+      for &i in &indices {
+        if let Some((input, output)) = tangent::handles(&keys, i, slopes[i], clamped) {
+          keys[i].interpolation = Interpolation::StrokeBezier(input, output);
+          model.channel_mut().tangents[i] = TangentMode::Aligned;
+        }
+      }
+
+      model.channel_mut().spline = Spline::from_vec(keys);
+      model.update_handles();
+      model.touch();
+    })
+  }
+
+  /// Toggle the interpolation of the selected keys to something else.
+  ///
+  /// All the selected keys switch to the interpolation mode following the one of the first
+  /// selected key, so that a group of keys with mixed interpolations ends up with the same one.
+  pub fn toggle_interpolation(&mut self) {
+    let first = match self.selected_keys().first() {
+      Some(&i) => self.channel().spline.keys()[i],
+      None => return,
+    };
+    let kind = InterpolationKind::of(&first.interpolation)
+      .map_or(InterpolationKind::Linear, InterpolationKind::next);
+
+    self.set_interpolation(kind);
+  }
+}
+
+/// Move the handles of a key, if any, by `delta`.
+fn carry_handles(key: &mut Key<f32, ScreenPos>, delta: ScreenPos) {
+  match key.interpolation {
+    Interpolation::Bezier(ref mut h) => *h += delta,
+
+    Interpolation::StrokeBezier(ref mut input, ref mut output) => {
+      *input += delta;
+      *output += delta;
+    }
+
+    _ => (),
+  }
+}
+
+/// Possible errors that might occur while using the model.
+#[derive(Debug)]
+pub enum EditorError {
+  /// Unknown key index (i.e. likely out of bounds).
+  UnknownKey(usize),
+  /// Wrong interpolation assumed (typical for Bézier).
+  WrongInterpolationAssumed(usize),
+  /// No curve segment at the given time.
+  NoSegment(f32),
+  /// Unknown channel index.
+  UnknownChannel(usize),
+  /// The channel is locked or hidden, and cannot be edited.
+  ReadOnlyChannel(usize),
+  /// Keys cannot be moved through the current projection, or there is none.
+  ProjectionNotEditable,
+  /// A time or value is infinite or NaN.
+  InvalidValue(f32),
+  /// Error while loading or saving a document.
+  DocumentError(DocumentError),
+}
+
+impl fmt::Display for EditorError {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    match *self {
+      EditorError::UnknownKey(i) => write!(f, "unknown key {}", i),
+      EditorError::WrongInterpolationAssumed(i) => {
+        write!(f, "wrong interpolation assumed for key {}", i)
+      }
+      EditorError::NoSegment(t) => write!(f, "no curve segment at t = {}", t),
+      EditorError::UnknownChannel(i) => write!(f, "unknown channel {}", i),
+      EditorError::ReadOnlyChannel(i) => write!(f, "channel {} is locked or hidden", i),
+      EditorError::ProjectionNotEditable => f.write_str("keys cannot be moved in this view"),
+      EditorError::InvalidValue(x) => write!(f, "invalid value {}", x),
+      EditorError::DocumentError(ref e) => write!(f, "document error: {}", e),
+    }
+  }
+}
+
+/// A selection. It can either be a control point (Key) or a handle for a Bézier curve. In case
+/// of a handle, we either select the “real” handle or its mirrored sibling.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Selection {
+  /// A selected control point.
+  Key(usize),
+  /// A selected handle.
+  Handle(usize, HandleSelection),
+  /// The threshold marker of the step segment starting at a key, where the curve jumps.
+  Threshold(usize),
+}
+
+impl Selection {
+  /// Index of the key the selection belongs to.
+  pub fn key(self) -> usize {
+    match self {
+      Selection::Key(i) | Selection::Handle(i, _) | Selection::Threshold(i) => i,
+    }
+  }
+
+  /// Change the key index the selection refers to.
+  fn remap(&mut self, f: impl Fn(usize) -> usize) {
+    match *self {
+      Selection::Key(ref mut i)
+      | Selection::Handle(ref mut i, _)
+      | Selection::Threshold(ref mut i) => *i = f(*i),
+    }
+  }
+}
+
+/// Part of handle being selected.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum HandleSelection {
+  /// The actual handle of the control point.
+  Own,
+  /// Mirror handle of the control point.
+  Mirror,
+}
+
+/// A mouse drag in progress.
+#[derive(Debug)]
+struct Drag {
+  // Content grabbed by the cursor.
+  grabbed: Selection,
+  // Position where the drag started.
+  anchor: ScreenPos,
+  // Dragged keys along with their positions when the drag started.
+  origins: Vec<(usize, ScreenPos)>,
+}
+
+/// Selection and drag of a channel, set aside while other channels are edited.
+#[derive(Debug)]
+struct DetachedSelection {
+  // Channel the selection belongs to.
+  active: usize,
+  // Identifiers of the keys of the channel, by index, when the selection was set aside.
+  ids: Vec<KeyId>,
+  // Selection and drag, with indices in the channel as it was then.
+  selection: Vec<Selection>,
+  drag: Option<Drag>,
+}
@@ -9,46 +9,52 @@ out vec2 g_point;
 out float g_radius;
 out vec3 g_color;
 
+uniform float aspect_ratio;
+
+vec4 to_ndc(vec2 p) {
+  return vec4(p.x / aspect_ratio, p.y, 0., 1.);
+}
+
 void main() {
   vec2 p = gl_in[0].gl_Position.xy;
   float r = v_radius[0];
   vec3 color = v_color[0];
 
-  gl_Position = vec4(p + vec2(-r, -r), 0., 1.);
+  g_point = p + vec2(-r, -r);
+  gl_Position = to_ndc(g_point);
   g_center = p;
-  g_point = gl_Position.xy;
   g_radius = r;
   g_color = color;
   EmitVertex();
-  gl_Position = vec4(p + vec2(r, -r), 0., 1.);
+  g_point = p + vec2(r, -r);
+  gl_Position = to_ndc(g_point);
   g_center = p;
-  g_point = gl_Position.xy;
   g_radius = r;
   g_color = color;
   EmitVertex();
-  gl_Position = vec4(p + vec2(-r, r), 0., 1.);
+  g_point = p + vec2(-r, r);
+  gl_Position = to_ndc(g_point);
   g_center = p;
-  g_point = gl_Position.xy;
   g_radius = r;
   g_color = color;
   EmitVertex();
   EndPrimitive();
 
-  gl_Position = vec4(p + vec2(r, -r), 0., 1.);
+  g_point = p + vec2(r, -r);
+  gl_Position = to_ndc(g_point);
   g_center = p;
-  g_point = gl_Position.xy;
   g_radius = r;
   g_color = color;
   EmitVertex();
-  gl_Position = vec4(p + vec2(r, r), 0., 1.);
+  g_point = p + vec2(r, r);
+  gl_Position = to_ndc(g_point);
   g_center = p;
-  g_point = gl_Position.xy;
   g_radius = r;
   g_color = color;
   EmitVertex();
-  gl_Position = vec4(p + vec2(-r, r), 0., 1.);
+  g_point = p + vec2(-r, r);
+  gl_Position = to_ndc(g_point);
   g_center = p;
-  g_point = gl_Position.xy;
   g_radius = r;
   g_color = color;
   EmitVertex();
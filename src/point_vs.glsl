@@ -5,8 +5,12 @@ in vec3 color;
 out float v_radius;
 out vec3 v_color;
 
+uniform vec2 view_center;
+uniform vec2 view_scale;
+
 void main() {
   v_radius = radius;
   v_color = color;
-  gl_Position = vec4(position.x * 2. - 1., position.y, 0., 1.);
+  // the position is kept in view space; the geometry shader applies the aspect ratio
+  gl_Position = vec4((position - view_center) * view_scale, 0., 1.);
 }
@@ -0,0 +1,182 @@
+//! Scalar curves.
+//!
+//! Animation channels are usually `Spline<f32, f32>`: a single value per key. The editor stores
+//! 2D values, and shows such curves in
+//! [`CurveMode::Function`](crate::mode::CurveMode::Function) as graphs: keys sit at `(t, v)` and
+//! Bézier handles at `(t, v)` too, on the tangent of the graph at the time their control point
+//! stands for — a third of the segment away for cubic segments and half of it for quadratic ones.
+//! Only the vertical coordinate of values and handles then matters, so converting to and from
+//! scalar splines is lossless.
+
+use splines::{Interpolation, Key, Spline};
+use std::{fs::File, io::BufReader, path::Path};
+
+use crate::{document::DocumentError, flatten::is_bezier, model::ScreenPos};
+
+/// Shortest horizontal offset, relative to the one they get pinned at, from which the slope of
+/// handles is measured. Handles dragged closer to their key — or past it — get a steep tangent.
+const MIN_HANDLE_OFFSET: f32 = 0.01;
+
+/// Scalar version of a spline, keeping the vertical coordinate of its values and handles.
+pub fn to_scalar(spline: &Spline<f32, ScreenPos>) -> Spline<f32, f32> {
+  let keys = spline
+    .keys()
+    .iter()
+    .map(|key| {
+      let interpolation = match key.interpolation {
+        Interpolation::Step(threshold) => Interpolation::Step(threshold),
+        Interpolation::Linear => Interpolation::Linear,
+        Interpolation::Cosine => Interpolation::Cosine,
+        Interpolation::CatmullRom => Interpolation::CatmullRom,
+        Interpolation::Bezier(u) => Interpolation::Bezier(u.y),
+        Interpolation::StrokeBezier(input, output) => {
+          Interpolation::StrokeBezier(input.y, output.y)
+        }
+        _ => Interpolation::Linear,
+      };
+
+      Key::new(key.t, key.value.y, interpolation)
+    })
+    .collect();
+
+  Spline::from_vec(keys)
+}
+
+/// Load a scalar spline from a JSON file, as written by
+/// [`export_spline`](crate::export::export_spline).
+pub fn load(path: impl AsRef<Path>) -> Result<Spline<f32, f32>, DocumentError> {
+  let file = File::open(path).map_err(DocumentError::IOError)?;
+  serde_json::from_reader(BufReader::new(file)).map_err(DocumentError::FormatError)
+}
+
+/// Spline drawing the graph of a scalar spline in
+/// [`CurveMode::Function`](crate::mode::CurveMode::Function).
+///
+/// Keys are placed at `(t, v)` and handles at their time in the graph; see [`place_handles`].
+pub fn from_scalar(spline: &Spline<f32, f32>) -> Spline<f32, ScreenPos> {
+  let mut keys: Vec<_> = spline
+    .keys()
+    .iter()
+    .map(|key| {
+      let handle = |v: f32| ScreenPos::new(key.t, v);
+      let interpolation = match key.interpolation {
+        Interpolation::Step(threshold) => Interpolation::Step(threshold),
+        Interpolation::Linear => Interpolation::Linear,
+        Interpolation::Cosine => Interpolation::Cosine,
+        Interpolation::CatmullRom => Interpolation::CatmullRom,
+        Interpolation::Bezier(u) => Interpolation::Bezier(handle(u)),
+        Interpolation::StrokeBezier(input, output) => {
+          Interpolation::StrokeBezier(handle(input), handle(output))
+        }
+        _ => Interpolation::Linear,
+      };
+
+      Key::new(key.t, handle(key.value), interpolation)
+    })
+    .collect();
+
+  // handles only get their time once all the keys are known
+  place_handles(&mut keys);
+
+  Spline::from_vec(keys)
+}
+
+/// Time offsets of the input and output handles of the key at index `i` in the graph of a
+/// function curve: the input handle stands `offsets.0` before the key and the output one
+/// `offsets.1` after it.
+///
+/// A handle without a segment on its side uses the offset of the other one. `None` is returned if
+/// the key has no neighbour.
+pub fn handle_offsets(keys: &[Key<f32, ScreenPos>], i: usize) -> Option<(f32, f32)> {
+  let key = keys.get(i)?;
+  let prev = i.checked_sub(1).and_then(|j| keys.get(j));
+  let next = keys.get(i + 1);
+
+  let input = prev.map(|prev| (key.t - prev.t) / 3.);
+  let output = next.map(|next| {
+    if is_bezier(&next.interpolation) {
+      (next.t - key.t) / 3.
+    } else {
+      (next.t - key.t) * 0.5
+    }
+  });
+
+  match (input, output) {
+    (Some(input), Some(output)) => Some((input, output)),
+    (Some(offset), None) | (None, Some(offset)) => Some((offset, offset)),
+    (None, None) => None,
+  }
+}
+
+/// Move the handles of all the keys horizontally to the time they stand for in the graph of a
+/// function curve, keeping the slope of their tangents. Return whether any handle moved.
+///
+/// This keeps handles meaningful as `(t, v)` tangents of the graph when keys or handles move:
+/// wherever a handle is dragged, the tangent goes through it.
+pub fn pin_handles(keys: &mut [Key<f32, ScreenPos>]) -> bool {
+  let mut changed = false;
+
+  for i in 0..keys.len() {
+    let (input, output) = match handle_offsets(keys, i) {
+      Some(offsets) => offsets,
+      None => continue,
+    };
+    let key = &mut keys[i];
+    let interpolation = match key.interpolation {
+      Interpolation::Bezier(u) => Interpolation::Bezier(pin(key.value, u, output)),
+      Interpolation::StrokeBezier(a, b) => {
+        Interpolation::StrokeBezier(pin(key.value, a, -input), pin(key.value, b, output))
+      }
+      _ => continue,
+    };
+
+    if interpolation != key.interpolation {
+      key.interpolation = interpolation;
+      changed = true;
+    }
+  }
+
+  changed
+}
+
+/// Move the handles of all the keys horizontally to the time they stand for in the graph of a
+/// function curve, keeping their value.
+///
+/// Only the vertical coordinate of handles shapes the curve, so it doesn’t change. This is meant
+/// for curves whose handles were placed without caring about their time, such as imported ones;
+/// see [`pin_handles`] for curves being edited.
+pub fn place_handles(keys: &mut [Key<f32, ScreenPos>]) {
+  for i in 0..keys.len() {
+    if let Some((input, output)) = handle_offsets(keys, i) {
+      let t = keys[i].t;
+
+      match keys[i].interpolation {
+        Interpolation::Bezier(ref mut u) => u.x = t + output,
+
+        Interpolation::StrokeBezier(ref mut a, ref mut b) => {
+          a.x = t - input;
+          b.x = t + output;
+        }
+
+        _ => (),
+      }
+    }
+  }
+}
+
+/// Move a handle of a key at `p` along its tangent to the time offset `dt`.
+fn pin(p: ScreenPos, handle: ScreenPos, dt: f32) -> ScreenPos {
+  let t = p.x + dt;
+
+  if handle.x == t {
+    return handle;
+  } else if dt == 0. {
+    return ScreenPos::new(t, handle.y);
+  }
+
+  // measure the offset on the side the handle should be, so that the tangent doesn’t flip
+  let dx = ((handle.x - p.x) / dt).max(MIN_HANDLE_OFFSET) * dt;
+  let slope = (handle.y - p.y) / dx;
+
+  ScreenPos::new(t, p.y + slope * dt)
+}
@@ -0,0 +1,18 @@
+use luminance::{shader::Uniform, UniformInterface};
+
+/// Uniforms shared by the line and point shaders.
+#[derive(Debug, UniformInterface)]
+pub struct ShaderInterface {
+  /// Curve-space position at the center of the view.
+  #[uniform(name = "view_center")]
+  pub view_center: Uniform<[f32; 2]>,
+  /// Scale from curve space to normalized device coordinates.
+  #[uniform(name = "view_scale")]
+  pub view_scale: Uniform<[f32; 2]>,
+  /// Aspect ratio of the framebuffer.
+  #[uniform(name = "aspect_ratio")]
+  pub aspect_ratio: Uniform<f32>,
+  /// Size of a window unit in view space; line widths are expressed in window units.
+  #[uniform(unbound, name = "pixel_size")]
+  pub pixel_size: Uniform<f32>,
+}
@@ -0,0 +1,242 @@
+//! Slot buffers.
+//!
+//! A [`SlotBuffer`] is the CPU-side mirror of a GPU buffer holding the data of several items —
+//! curve segments, keys… Each item owns a slot: a range of the buffer with some spare room, so
+//! that it can grow a bit without moving. Updating an item only touches its slot, and the buffer
+//! keeps track of the ranges that changed since the last upload, so that only those need to be
+//! written to the GPU buffer.
+
+use std::ops::Range;
+
+/// Smallest capacity of a slot.
+const MIN_SLOT_CAPACITY: usize = 4;
+/// Smallest capacity of a buffer.
+const MIN_BUFFER_CAPACITY: usize = 64;
+
+/// Growable buffer split into per-item slots.
+#[derive(Debug)]
+pub struct SlotBuffer<T> {
+  // Mirrored content; its length is the capacity of the GPU buffer.
+  data: Vec<T>,
+  // Slot of each item, if any.
+  slots: Vec<Option<Slot>>,
+  // Value filling the parts of the buffer not used by any item.
+  fill: T,
+  // End of the last slot.
+  end: usize,
+  // Ranges modified since the last upload, sorted and disjoint.
+  dirty: Vec<Range<usize>>,
+  // Whether the capacity changed since the last upload.
+  grown: bool,
+}
+
+/// Part of a buffer owned by an item.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Slot {
+  /// Start of the slot in the buffer.
+  pub start: usize,
+  /// Number of elements in use.
+  pub len: usize,
+  /// Number of elements the slot can hold.
+  pub capacity: usize,
+}
+
+impl<T> SlotBuffer<T>
+where
+  T: Copy + PartialEq,
+{
+  /// Create an empty buffer. Unused parts of the buffer are filled with `fill`.
+  pub fn new(fill: T) -> Self {
+    SlotBuffer {
+      data: vec![fill; MIN_BUFFER_CAPACITY],
+      slots: Vec::new(),
+      fill,
+      end: 0,
+      dirty: Vec::new(),
+      grown: true,
+    }
+  }
+
+  /// Whole mirrored content, including unused parts.
+  pub fn data(&self) -> &[T] {
+    &self.data
+  }
+
+  /// Number of elements up to the end of the last slot; anything after is unused.
+  pub fn used(&self) -> usize {
+    self.end
+  }
+
+  /// Number of items.
+  pub fn len(&self) -> usize {
+    self.slots.len()
+  }
+
+  /// Check whether there is no item.
+  pub fn is_empty(&self) -> bool {
+    self.slots.is_empty()
+  }
+
+  /// Slot of an item, if any.
+  pub fn slot(&self, item: usize) -> Option<Slot> {
+    self.slots.get(item).cloned().flatten()
+  }
+
+  /// Slot an item would get if set to `len` elements now.
+  ///
+  /// This is useful when the content of the item depends on its position in the buffer, as
+  /// indices do.
+  pub fn reserve(&mut self, item: usize, len: usize) -> Slot {
+    match self.slot(item) {
+      Some(slot) if len <= slot.capacity => slot,
+
+      _ => {
+        // the item doesn’t fit anymore (or has no slot yet): move it at the end
+        self.release(item);
+
+        let capacity = len.max(MIN_SLOT_CAPACITY).next_power_of_two();
+        let slot = Slot {
+          start: self.end,
+          len: 0,
+          capacity,
+        };
+
+        self.end += capacity;
+        self.grow(self.end);
+
+        if self.slots.len() <= item {
+          self.slots.resize(item + 1, None);
+        }
+
+        self.slots[item] = Some(slot);
+        slot
+      }
+    }
+  }
+
+  /// Set the content of an item. Return its slot.
+  ///
+  /// Nothing is marked as modified if the content didn’t change.
+  pub fn set(&mut self, item: usize, content: &[T]) -> Slot {
+    let mut slot = self.reserve(item, content.len());
+    let range = slot.start..slot.start + slot.len.max(content.len());
+
+    // pad with the fill value if the content shrinks
+    let changed = range.clone().any(|i| {
+      let value = content.get(i - slot.start).copied().unwrap_or(self.fill);
+      self.data[i] != value
+    });
+
+    if changed {
+      for i in range.clone() {
+        self.data[i] = content.get(i - slot.start).copied().unwrap_or(self.fill);
+      }
+
+      self.mark_dirty(range);
+    }
+
+    slot.len = content.len();
+    self.slots[item] = Some(slot);
+    slot
+  }
+
+  /// Remove the content of an item, freeing its slot.
+  pub fn clear(&mut self, item: usize) {
+    self.release(item);
+  }
+
+  /// Remove all the items from `len` on.
+  pub fn truncate(&mut self, len: usize) {
+    for item in len..self.slots.len() {
+      self.release(item);
+    }
+
+    self.slots.truncate(len);
+    self.end = self
+      .slots
+      .iter()
+      .flatten()
+      .map(|slot| slot.start + slot.capacity)
+      .max()
+      .unwrap_or(0);
+  }
+
+  /// Repack all the slots at the beginning of the buffer if too much room is lost to slots that
+  /// have been moved. Return whether the buffer was repacked.
+  pub fn compact(&mut self) -> bool {
+    let live: usize = self.slots.iter().flatten().map(|slot| slot.capacity).sum();
+
+    if self.end <= 2 * live || self.end <= MIN_BUFFER_CAPACITY {
+      return false;
+    }
+
+    let mut data = vec![self.fill; self.data.len()];
+    let mut end = 0;
+
+    for slot in self.slots.iter_mut().flatten() {
+      data[end..end + slot.len].copy_from_slice(&self.data[slot.start..slot.start + slot.len]);
+      slot.start = end;
+      end += slot.capacity;
+    }
+
+    self.data = data;
+    self.end = end;
+    self.mark_dirty(0..self.data.len());
+    true
+  }
+
+  /// Take the ranges modified since the last call, sorted and disjoint.
+  pub fn take_dirty(&mut self) -> Vec<Range<usize>> {
+    std::mem::take(&mut self.dirty)
+  }
+
+  /// Check whether the capacity changed since the last call, in which case the GPU buffer must be
+  /// reallocated with the whole [`SlotBuffer::data`].
+  pub fn take_grown(&mut self) -> bool {
+    std::mem::replace(&mut self.grown, false)
+  }
+
+  /// Free the slot of an item, filling it.
+  fn release(&mut self, item: usize) {
+    if let Some(slot) = self.slots.get_mut(item).and_then(Option::take) {
+      let range = slot.start..slot.start + slot.len;
+
+      for x in &mut self.data[range.clone()] {
+        *x = self.fill;
+      }
+
+      self.mark_dirty(range);
+    }
+  }
+
+  /// Make sure the buffer can hold at least `len` elements, doubling its capacity if needed.
+  fn grow(&mut self, len: usize) {
+    if len > self.data.len() {
+      let capacity = len.next_power_of_two().max(MIN_BUFFER_CAPACITY);
+      self.data.resize(capacity, self.fill);
+      self.grown = true;
+    }
+  }
+
+  /// Mark a range as modified, merging it with the ranges it touches.
+  fn mark_dirty(&mut self, range: Range<usize>) {
+    if range.start >= range.end {
+      return;
+    }
+
+    let mut merged = range;
+    let mut ranges = Vec::with_capacity(self.dirty.len() + 1);
+
+    for r in self.dirty.drain(..) {
+      if r.end < merged.start || merged.end < r.start {
+        ranges.push(r);
+      } else {
+        merged = merged.start.min(r.start)..merged.end.max(r.end);
+      }
+    }
+
+    ranges.push(merged);
+    ranges.sort_by_key(|r| r.start);
+    self.dirty = ranges;
+  }
+}
@@ -0,0 +1,109 @@
+//! Snapping.
+//!
+//! When snapping is enabled, positions passed to the editing operations are quantized before
+//! being applied: to the [`Grid`], to the time and value of other keys, or to integer frames.
+
+use cgmath::Vector2;
+use splines::Key;
+
+use crate::{grid::Grid, model::ScreenPos};
+
+/// What positions snap to.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum SnapTarget {
+  /// Intersections of the minor grid lines.
+  Grid,
+  /// Time and value of other keys, independently, when close enough.
+  Keys,
+  /// Integer frames; only the time is quantized.
+  Frames,
+}
+
+impl SnapTarget {
+  /// The next target, cycling.
+  pub fn next(self) -> Self {
+    match self {
+      SnapTarget::Grid => SnapTarget::Keys,
+      SnapTarget::Keys => SnapTarget::Frames,
+      SnapTarget::Frames => SnapTarget::Grid,
+    }
+  }
+}
+
+/// Snapping settings.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Snapping {
+  /// Whether snapping is applied.
+  pub enabled: bool,
+  /// What positions snap to.
+  pub target: SnapTarget,
+  /// Number of frames per time unit, used by [`SnapTarget::Frames`].
+  pub frame_rate: f32,
+  /// Maximum distance, in curve space and per axis, at which positions snap to other keys.
+  pub tolerance: Vector2<f32>,
+}
+
+impl Default for Snapping {
+  fn default() -> Self {
+    Snapping {
+      enabled: false,
+      target: SnapTarget::Grid,
+      frame_rate: 24.,
+      tolerance: Vector2::new(0.02, 0.02),
+    }
+  }
+}
+
+impl Snapping {
+  /// Snap a position, if enabled.
+  ///
+  /// `keys` are the keys that can be snapped to when snapping to keys; the ones whose index is in
+  /// `ignored` are skipped — typically, the keys being moved.
+  pub fn apply(
+    &self,
+    p: ScreenPos,
+    grid: &Grid,
+    keys: &[Key<f32, ScreenPos>],
+    ignored: &[usize],
+  ) -> ScreenPos {
+    if !self.enabled {
+      return p;
+    }
+
+    match self.target {
+      SnapTarget::Grid => grid.snap(p),
+
+      SnapTarget::Keys => {
+        let others = keys
+          .iter()
+          .enumerate()
+          .filter(|(i, _)| !ignored.contains(i))
+          .map(|(_, key)| key.value);
+        let mut snapped = p;
+        let mut best = self.tolerance;
+
+        for q in others {
+          let d = q - p;
+
+          if d.x.abs() <= best.x {
+            best.x = d.x.abs();
+            snapped.x = q.x;
+          }
+
+          if d.y.abs() <= best.y {
+            best.y = d.y.abs();
+            snapped.y = q.y;
+          }
+        }
+
+        snapped
+      }
+
+      SnapTarget::Frames if self.frame_rate > 0. => {
+        ScreenPos::new((p.x * self.frame_rate).round() / self.frame_rate, p.y)
+      }
+
+      SnapTarget::Frames => p,
+    }
+  }
+}
@@ -0,0 +1,328 @@
+//! 3D curves.
+//!
+//! In [`CurveMode::Space`](crate::mode::CurveMode::Space), a document describes a 3D path — a
+//! camera path for instance: the function curves of the channels named after [`COMPONENTS`] are
+//! the coordinates of the position at each time. A missing or empty component is zero. Such a
+//! path converts losslessly to and from a `Spline<f32, Vector3<f32>>` as long as all the
+//! components have keys at the same times, with the same interpolation; see
+//! [`SpaceCurve::to_spline`].
+//!
+//! Besides the component curves, the path can be shown through a [`Projection`]: orthographic
+//! views from the top, the front or the side, where keys are picked and moved in the projected
+//! plane, and an [`Orbit`] perspective preview.
+
+use cgmath::{InnerSpace as _, Vector3};
+use splines::{Interpolation, Key, Spline};
+use std::{fs::File, io::BufReader, path::Path};
+
+use crate::{document::DocumentError, export::ExportError, model::ScreenPos, scalar};
+
+/// Names of the channels holding the coordinates of a 3D path, in XYZ order, and the colors the
+/// channels are drawn with.
+pub const COMPONENTS: [(&str, [f32; 3]); 3] = [
+  ("x", [0.9, 0.3, 0.3]),
+  ("y", [0.3, 0.8, 0.3]),
+  ("z", [0.35, 0.5, 1.]),
+];
+
+/// Radians the orbit view turns by per view unit the cursor moves.
+const ORBIT_SPEED: f32 = 2.;
+/// Steepest pitch of the orbit view, in radians, so that it never looks straight up or down.
+const MAX_PITCH: f32 = 1.5;
+/// Distance of the orbit view to its pivot, relative to the radius of the keys around it.
+const ORBIT_DISTANCE: f32 = 2.5;
+/// Closest depth, relative to the distance to the pivot, at which points are still projected.
+const NEAR_PLANE: f32 = 0.05;
+
+/// A 3D path, read from the component curves of a set of channels.
+#[derive(Clone, Copy, Debug)]
+pub struct SpaceCurve<'a> {
+  components: [Option<&'a Spline<f32, ScreenPos>>; 3],
+}
+
+impl<'a> SpaceCurve<'a> {
+  /// Path made of the channels named after the [`COMPONENTS`], given as `(name, spline)` pairs.
+  /// The first channel of each name is used.
+  pub fn new<I>(channels: I) -> Self
+  where
+    I: IntoIterator<Item = (&'a str, &'a Spline<f32, ScreenPos>)>,
+  {
+    let mut components = [None; 3];
+
+    for (name, spline) in channels {
+      if let Some(c) = component_index(name) {
+        components[c] = components[c].or(Some(spline));
+      }
+    }
+
+    SpaceCurve { components }
+  }
+
+  /// Position at time `t`; before the first key and after the last one, the position of the
+  /// closest key.
+  ///
+  /// `None` is returned if a component cannot be sampled at that time.
+  pub fn position(&self, t: f32) -> Option<Vector3<f32>> {
+    let mut p = Vector3::new(0., 0., 0.);
+
+    for (c, spline) in self.components.iter().enumerate() {
+      if let Some(spline) = spline.filter(|spline| !spline.is_empty()) {
+        p[c] = spline.clamped_sample(t)?.y;
+      }
+    }
+
+    Some(p)
+  }
+
+  /// Times of the keys of all the components, sorted and without duplicates.
+  pub fn key_times(&self) -> Vec<f32> {
+    let mut times: Vec<_> = self
+      .components
+      .iter()
+      .flatten()
+      .flat_map(|spline| spline.keys().iter().map(|key| key.t))
+      .collect();
+    times.sort_by(f32::total_cmp);
+    times.dedup();
+    times
+  }
+
+  /// `Spline<f32, Vector3<f32>>` following the path.
+  ///
+  /// Every component must have a key at the time of each key of the others, with the same
+  /// interpolation mode; Bézier handles then combine into 3D handles.
+  pub fn to_spline(&self) -> Result<Spline<f32, Vector3<f32>>, ExportError> {
+    let components: Vec<_> = self
+      .components
+      .iter()
+      .map(|spline| spline.map_or(Spline::from_vec(Vec::new()), scalar::to_scalar))
+      .collect();
+    let times = self.key_times();
+
+    if times.is_empty() {
+      return Err(ExportError::EmptySpline);
+    }
+
+    let keys = times
+      .iter()
+      .map(|&t| {
+        let mut keys = components
+          .iter()
+          .map(|spline| spline.keys().iter().find(|key| key.t == t).copied());
+        let (x, y, z) = match (keys.next(), keys.next(), keys.next()) {
+          (Some(Some(x)), Some(Some(y)), Some(Some(z))) => (x, y, z),
+          _ => return Err(ExportError::UnalignedComponents(t)),
+        };
+
+        let v = |x, y, z| Vector3::new(x, y, z);
+        let interpolation = match (x.interpolation, y.interpolation, z.interpolation) {
+          (Interpolation::Step(a), Interpolation::Step(b), Interpolation::Step(c))
+            if a == b && b == c =>
+          {
+            Interpolation::Step(a)
+          }
+          (Interpolation::Linear, Interpolation::Linear, Interpolation::Linear) => {
+            Interpolation::Linear
+          }
+          (Interpolation::Cosine, Interpolation::Cosine, Interpolation::Cosine) => {
+            Interpolation::Cosine
+          }
+          (Interpolation::CatmullRom, Interpolation::CatmullRom, Interpolation::CatmullRom) => {
+            Interpolation::CatmullRom
+          }
+          (Interpolation::Bezier(a), Interpolation::Bezier(b), Interpolation::Bezier(c)) => {
+            Interpolation::Bezier(v(a, b, c))
+          }
+          (
+            Interpolation::StrokeBezier(a0, a1),
+            Interpolation::StrokeBezier(b0, b1),
+            Interpolation::StrokeBezier(c0, c1),
+          ) => Interpolation::StrokeBezier(v(a0, b0, c0), v(a1, b1, c1)),
+          _ => return Err(ExportError::UnalignedComponents(t)),
+        };
+
+        Ok(Key::new(t, v(x.value, y.value, z.value), interpolation))
+      })
+      .collect::<Result<_, _>>()?;
+
+    Ok(Spline::from_vec(keys))
+  }
+}
+
+/// Index of the coordinate a channel holds, from its name.
+pub fn component_index(name: &str) -> Option<usize> {
+  COMPONENTS.iter().position(|&(n, _)| n == name)
+}
+
+/// Scalar splines of the coordinates of a 3D spline, in XYZ order.
+pub fn components(spline: &Spline<f32, Vector3<f32>>) -> [Spline<f32, f32>; 3] {
+  [0, 1, 2].map(|c| {
+    let keys = spline
+      .keys()
+      .iter()
+      .map(|key| {
+        let interpolation = match key.interpolation {
+          Interpolation::Step(threshold) => Interpolation::Step(threshold),
+          Interpolation::Linear => Interpolation::Linear,
+          Interpolation::Cosine => Interpolation::Cosine,
+          Interpolation::CatmullRom => Interpolation::CatmullRom,
+          Interpolation::Bezier(u) => Interpolation::Bezier(u[c]),
+          Interpolation::StrokeBezier(input, output) => {
+            Interpolation::StrokeBezier(input[c], output[c])
+          }
+          _ => Interpolation::Linear,
+        };
+
+        Key::new(key.t, key.value[c], interpolation)
+      })
+      .collect();
+
+    Spline::from_vec(keys)
+  })
+}
+
+/// Load a 3D spline from a JSON file, as written by
+/// [`export_space`](crate::export::export_space).
+pub fn load(path: impl AsRef<Path>) -> Result<Spline<f32, Vector3<f32>>, DocumentError> {
+  let file = File::open(path).map_err(DocumentError::IOError)?;
+  serde_json::from_reader(BufReader::new(file)).map_err(DocumentError::FormatError)
+}
+
+/// How a 3D path is shown instead of its component curves.
+///
+/// Orthographic views map the path to a plane, in which keys are picked and moved; the third
+/// coordinate is left untouched. The y axis points up, and the front view looks along -z.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Projection {
+  /// Looking down: x to the right and -z up.
+  Top,
+  /// Looking along -z: x to the right and y up.
+  Front,
+  /// Looking along -x: -z to the right and y up.
+  Side,
+  /// Perspective preview, which cannot be edited.
+  Orbit(Orbit),
+}
+
+impl Projection {
+  /// Next projection in cycling order, `None` standing for the component curves. The orbit view
+  /// starts framing the keys of `curve`.
+  pub fn cycle(projection: Option<Self>, curve: &SpaceCurve) -> Option<Self> {
+    match projection {
+      None => Some(Projection::Top),
+      Some(Projection::Top) => Some(Projection::Front),
+      Some(Projection::Front) => Some(Projection::Side),
+      Some(Projection::Side) => Some(Projection::Orbit(Orbit::around(curve))),
+      Some(Projection::Orbit(_)) => None,
+    }
+  }
+
+  /// Short, human-readable name.
+  pub fn name(self) -> &'static str {
+    match self {
+      Projection::Top => "top",
+      Projection::Front => "front",
+      Projection::Side => "side",
+      Projection::Orbit(_) => "orbit",
+    }
+  }
+
+  /// Axes and directions of the horizontal and vertical coordinates of orthographic views.
+  fn axes(self) -> Option<[(usize, f32); 2]> {
+    match self {
+      Projection::Top => Some([(0, 1.), (2, -1.)]),
+      Projection::Front => Some([(0, 1.), (1, 1.)]),
+      Projection::Side => Some([(2, -1.), (1, 1.)]),
+      Projection::Orbit(_) => None,
+    }
+  }
+
+  /// Project a 3D position on the view plane.
+  ///
+  /// `None` is returned for positions behind the orbit view.
+  pub fn project(self, p: Vector3<f32>) -> Option<ScreenPos> {
+    match (self, self.axes()) {
+      (_, Some([(u, su), (v, sv)])) => Some(ScreenPos::new(p[u] * su, p[v] * sv)),
+      (Projection::Orbit(orbit), _) => orbit.project(p),
+      _ => None,
+    }
+  }
+
+  /// 3D position projected at `p`, taking the coordinate the view cannot show from `reference`.
+  ///
+  /// `None` is returned for the orbit view, which cannot be edited.
+  pub fn unproject(self, p: ScreenPos, reference: Vector3<f32>) -> Option<Vector3<f32>> {
+    let [(u, su), (v, sv)] = self.axes()?;
+    let mut q = reference;
+    q[u] = p.x * su;
+    q[v] = p.y * sv;
+    Some(q)
+  }
+}
+
+/// Perspective view turning around a pivot.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Orbit {
+  /// Point looked at.
+  pub pivot: Vector3<f32>,
+  /// Distance between the eye and the pivot.
+  pub distance: f32,
+  /// Rotation around the vertical axis, in radians.
+  pub yaw: f32,
+  /// Rotation around the horizontal axis, in radians; positive values look down.
+  pub pitch: f32,
+}
+
+impl Orbit {
+  /// Orbit looking at the keys of a path from the front and a bit above.
+  pub fn around(curve: &SpaceCurve) -> Self {
+    let points: Vec<_> = curve
+      .key_times()
+      .into_iter()
+      .filter_map(|t| curve.position(t))
+      .collect();
+    let pivot = if points.is_empty() {
+      Vector3::new(0., 0., 0.)
+    } else {
+      points.iter().fold(Vector3::new(0., 0., 0.), |a, &p| a + p) / points.len() as f32
+    };
+    let radius = points
+      .iter()
+      .map(|&p| (p - pivot).magnitude())
+      .fold(0., f32::max);
+
+    Orbit {
+      pivot,
+      distance: ORBIT_DISTANCE * radius.max(1.),
+      yaw: 0.5,
+      pitch: 0.4,
+    }
+  }
+
+  /// Turn around the pivot as the cursor moves by `view_delta`, in view space.
+  pub fn rotate(&mut self, view_delta: ScreenPos) {
+    self.yaw -= view_delta.x * ORBIT_SPEED;
+    self.pitch = (self.pitch - view_delta.y * ORBIT_SPEED).clamp(-MAX_PITCH, MAX_PITCH);
+  }
+
+  /// Perspective projection of a position, the pivot keeping its scale; `None` if the position
+  /// is behind the eye.
+  fn project(&self, p: Vector3<f32>) -> Option<ScreenPos> {
+    let q = p - self.pivot;
+
+    // turn around the vertical axis, then tilt
+    let (sin, cos) = self.yaw.sin_cos();
+    let q = Vector3::new(q.x * cos - q.z * sin, q.y, q.x * sin + q.z * cos);
+    let (sin, cos) = self.pitch.sin_cos();
+    let q = Vector3::new(q.x, q.y * cos - q.z * sin, q.y * sin + q.z * cos);
+
+    // the eye looks along -z
+    let depth = self.distance - q.z;
+
+    if depth < NEAR_PLANE * self.distance {
+      None
+    } else {
+      Some(ScreenPos::new(q.x, q.y) * (self.distance / depth))
+    }
+  }
+}
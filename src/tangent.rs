@@ -0,0 +1,327 @@
+//! Tangent modes of stroke Bézier keys.
+//!
+//! A [`Interpolation::StrokeBezier`] key has independent input and output handles. Its tangent
+//! mode constrains how they relate to each other, and is enforced every time the key or one of its
+//! handles moves.
+
+use cgmath::InnerSpace as _;
+use serde::{Deserialize, Serialize};
+use splines::{Interpolation, Key};
+
+use crate::{
+  flatten::is_bezier,
+  model::{HandleSelection, ScreenPos},
+};
+
+/// How the handles of a stroke Bézier key relate to each other.
+#[derive(Clone, Copy, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TangentMode {
+  /// Both handles move independently; the tangent can be broken.
+  #[default]
+  Free,
+  /// Handles stay collinear with the key, on opposite sides, with independent lengths.
+  Aligned,
+  /// Handles stay opposite with the same length.
+  Mirrored,
+  /// Handles are computed from the neighbouring keys and cannot be moved by hand.
+  Auto,
+}
+
+impl TangentMode {
+  /// All the modes, in cycling order.
+  pub const ALL: [TangentMode; 4] = [
+    TangentMode::Free,
+    TangentMode::Aligned,
+    TangentMode::Mirrored,
+    TangentMode::Auto,
+  ];
+
+  /// Next mode in cycling order.
+  pub fn next(self) -> Self {
+    let i = Self::ALL.iter().position(|&mode| mode == self).unwrap_or(0);
+    Self::ALL[(i + 1) % Self::ALL.len()]
+  }
+
+  /// Short, human-readable name.
+  pub fn name(self) -> &'static str {
+    match self {
+      TangentMode::Free => "free",
+      TangentMode::Aligned => "aligned",
+      TangentMode::Mirrored => "mirrored",
+      TangentMode::Auto => "auto",
+    }
+  }
+}
+
+/// Constrain the `input` and `output` handles of a key at `p` according to a tangent mode.
+///
+/// `leading` is the handle that keeps its position; the other one is moved to satisfy the mode.
+/// [`TangentMode::Auto`] handles are left untouched, see [`auto_handles`].
+pub fn constrain(
+  mode: TangentMode,
+  p: ScreenPos,
+  input: ScreenPos,
+  output: ScreenPos,
+  leading: HandleSelection,
+) -> (ScreenPos, ScreenPos) {
+  // the input handle is the “own” one of stroke Bézier keys
+  let (lead, other) = match leading {
+    HandleSelection::Own => (input, output),
+    HandleSelection::Mirror => (output, input),
+  };
+
+  let other = match mode {
+    TangentMode::Free | TangentMode::Auto => other,
+    TangentMode::Mirrored => 2. * p - lead,
+
+    TangentMode::Aligned => {
+      let dir = lead - p;
+
+      if dir.magnitude2() > 0. {
+        p - dir.normalize() * (other - p).magnitude()
+      } else {
+        other
+      }
+    }
+  };
+
+  match leading {
+    HandleSelection::Own => (lead, other),
+    HandleSelection::Mirror => (other, lead),
+  }
+}
+
+/// Scheme computing the tangents of keys from their neighbours.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum AutoTangent {
+  /// Slope between the previous and next keys, as with Catmull-Rom splines. Smooth, but the curve
+  /// might overshoot its keys.
+  Smooth,
+  /// Like [`AutoTangent::Smooth`], but flat on local extrema and limited so that the curve never
+  /// goes past the values of the keys around each segment.
+  Clamped,
+  /// Monotone cubic interpolation (Fritsch–Carlson): the curve is monotone wherever the keys are.
+  Monotone,
+}
+
+impl AutoTangent {
+  /// All the schemes, in cycling order.
+  pub const ALL: [AutoTangent; 3] = [
+    AutoTangent::Smooth,
+    AutoTangent::Clamped,
+    AutoTangent::Monotone,
+  ];
+
+  /// Next scheme in cycling order.
+  pub fn next(self) -> Self {
+    let i = Self::ALL
+      .iter()
+      .position(|&scheme| scheme == self)
+      .unwrap_or(0);
+    Self::ALL[(i + 1) % Self::ALL.len()]
+  }
+
+  /// Short, human-readable name.
+  pub fn name(self) -> &'static str {
+    match self {
+      AutoTangent::Smooth => "smooth",
+      AutoTangent::Clamped => "clamped",
+      AutoTangent::Monotone => "monotone",
+    }
+  }
+}
+
+/// Slope of the values at each key according to a scheme, with respect to time.
+///
+/// Each coordinate of the values is handled on its own, as a function of time. In
+/// [`CurveMode::Function`](crate::mode::CurveMode::Function), the horizontal coordinate of keys
+/// is their time, so its slope is always 1.
+pub fn slopes(keys: &[Key<f32, ScreenPos>], scheme: AutoTangent) -> Vec<ScreenPos> {
+  let xs: Vec<_> = keys.iter().map(|key| (key.t, key.value.x)).collect();
+  let ys: Vec<_> = keys.iter().map(|key| (key.t, key.value.y)).collect();
+
+  component_slopes(&xs, scheme)
+    .into_iter()
+    .zip(component_slopes(&ys, scheme))
+    .map(|(x, y)| ScreenPos::new(x, y))
+    .collect()
+}
+
+/// Slope at each point of a function of time given by `(t, value)` points, according to a scheme.
+fn component_slopes(points: &[(f32, f32)], scheme: AutoTangent) -> Vec<f32> {
+  let n = points.len();
+
+  // slope of each segment
+  let secants: Vec<f32> = points
+    .windows(2)
+    .map(|w| {
+      if w[1].0 > w[0].0 {
+        (w[1].1 - w[0].1) / (w[1].0 - w[0].0)
+      } else {
+        0.
+      }
+    })
+    .collect();
+
+  if secants.is_empty() {
+    return vec![0.; n];
+  }
+
+  match scheme {
+    AutoTangent::Smooth | AutoTangent::Clamped => (0..n)
+      .map(|i| {
+        if i == 0 {
+          return secants[0];
+        } else if i == n - 1 {
+          return secants[n - 2];
+        }
+
+        let (prev, point, next) = (points[i - 1], points[i], points[i + 1]);
+        let extremum = (point.1 - prev.1) * (next.1 - point.1) <= 0.;
+
+        if scheme == AutoTangent::Clamped && extremum {
+          0.
+        } else if next.0 > prev.0 {
+          (next.1 - prev.1) / (next.0 - prev.0)
+        } else {
+          0.
+        }
+      })
+      .collect(),
+
+    AutoTangent::Monotone => {
+      // initial tangents: average of the secants, flat where the direction changes
+      let mut m: Vec<f32> = (0..n)
+        .map(|i| {
+          if i == 0 {
+            secants[0]
+          } else if i == n - 1 {
+            secants[n - 2]
+          } else if secants[i - 1] * secants[i] <= 0. {
+            0.
+          } else {
+            (secants[i - 1] + secants[i]) * 0.5
+          }
+        })
+        .collect();
+
+      // restrict the tangents of each segment to the monotonicity region
+      for (k, &d) in secants.iter().enumerate() {
+        if d == 0. {
+          m[k] = 0.;
+          m[k + 1] = 0.;
+          continue;
+        }
+
+        let (a, b) = (m[k] / d, m[k + 1] / d);
+        let r = a * a + b * b;
+
+        if r > 9. {
+          let tau = 3. / r.sqrt();
+          m[k] = tau * a * d;
+          m[k + 1] = tau * b * d;
+        }
+      }
+
+      m
+    }
+  }
+}
+
+/// Input and output handles of the key at index `i` following a slope `m` of its value with
+/// respect to time, as given by [`slopes`].
+///
+/// Handles are placed so that time flows evenly along each segment: a third of the segment away
+/// for cubic segments and half of it for quadratic ones — segments ending on a key without input
+/// handle. With `clamped`, the coordinates of handles going past the value of the key at the
+/// other end of their segment are pulled back to it, each on its own, which keeps the curve from
+/// overshooting it. Handles of function curves thus keep their time.
+pub fn handles(
+  keys: &[Key<f32, ScreenPos>],
+  i: usize,
+  m: ScreenPos,
+  clamped: bool,
+) -> Option<(ScreenPos, ScreenPos)> {
+  let key = keys.get(i)?;
+  let prev = i.checked_sub(1).and_then(|j| keys.get(j));
+  let next = keys.get(i + 1);
+
+  // offset of a handle towards a neighbour at time span `dt` and value `target`
+  let offset = |dt: f32, target: Option<ScreenPos>| {
+    let offset = m * dt;
+
+    match target {
+      Some(target) if clamped => {
+        let room = target - key.value;
+        let scale = |room: f32, offset: f32| {
+          if offset.abs() > room.abs() {
+            (room / offset).abs()
+          } else {
+            1.
+          }
+        };
+
+        ScreenPos::new(
+          offset.x * scale(room.x, offset.x),
+          offset.y * scale(room.y, offset.y),
+        )
+      }
+      _ => offset,
+    }
+  };
+
+  let output = match next {
+    Some(next) if is_bezier(&next.interpolation) => offset((next.t - key.t) / 3., Some(next.value)),
+    Some(next) => offset((next.t - key.t) * 0.5, Some(next.value)),
+    None => offset(prev.map_or(0.1, |prev| (key.t - prev.t) / 3.), None),
+  };
+  let input = match prev {
+    Some(prev) => offset((key.t - prev.t) / 3., Some(prev.value)),
+    None => output,
+  };
+
+  Some((key.value - input, key.value + output))
+}
+
+/// Input and output handles of the key at index `i` in [`TangentMode::Auto`], computed from its
+/// neighbours with the [`AutoTangent::Smooth`] scheme.
+pub fn auto_handles(keys: &[Key<f32, ScreenPos>], i: usize) -> Option<(ScreenPos, ScreenPos)> {
+  keys.get(i)?;
+
+  let window = &keys[i.saturating_sub(1)..keys.len().min(i + 2)];
+  let m = slopes(window, AutoTangent::Smooth)[i - i.saturating_sub(1)];
+  handles(keys, i, m, false)
+}
+
+/// Enforce the tangent mode of the key at index `i`, if it’s a stroke Bézier key.
+///
+/// `leading` is the handle keeping its position for modes constraining one handle with the other.
+/// Return whether the key changed.
+pub fn enforce(
+  keys: &mut [Key<f32, ScreenPos>],
+  i: usize,
+  mode: TangentMode,
+  leading: HandleSelection,
+) -> bool {
+  let key = match keys.get(i) {
+    Some(key) => *key,
+    None => return false,
+  };
+
+  let (input, output) = match key.interpolation {
+    Interpolation::StrokeBezier(input, output) => (input, output),
+    _ => return false,
+  };
+
+  let (new_input, new_output) = match mode {
+    TangentMode::Auto => match auto_handles(keys, i) {
+      Some(handles) => handles,
+      None => return false,
+    },
+    mode => constrain(mode, key.value, input, output, leading),
+  };
+
+  keys[i].interpolation = Interpolation::StrokeBezier(new_input, new_output);
+  (new_input, new_output) != (input, output)
+}
@@ -7,14 +7,16 @@ pub enum Semantics {
   Radius,
   #[sem(name = "color", repr = "[f32; 3]", wrapper = "VColor")]
   Color,
+  #[sem(name = "width", repr = "f32", wrapper = "VWidth")]
+  Width,
 }
 
-#[derive(Clone, Copy, Debug, Vertex)]
+#[derive(Clone, Copy, Debug, PartialEq, Vertex)]
 #[vertex(sem = "Semantics")]
 #[repr(C)]
-pub struct LineVertex(pub VPos, pub VColor);
+pub struct LineVertex(pub VPos, pub VColor, pub VWidth);
 
-#[derive(Clone, Copy, Debug, Vertex)]
+#[derive(Clone, Copy, Debug, PartialEq, Vertex)]
 #[vertex(sem = "Semantics")]
 #[repr(C)]
 pub struct PointVertex(pub VPos, pub VColor, pub VRadius);
@@ -0,0 +1,949 @@
+//! Rendering side of the editor.
+//!
+//! The view turns an [`EditorModel`] into tessellations that can be rendered with luminance.
+
+use cgmath::{Vector2, Vector3};
+use luminance::{context::GraphicsContext, tess::Mode};
+use luminance_front::{
+  tess::{Interleaved, Tess, TessBuilder, TessError, TessMapError, TessView},
+  Backend,
+};
+use spline_editor::{
+  camera::Camera,
+  coords::Viewport,
+  flatten::{flatten_segment, segment_chord},
+  gradient::{Palette, SWATCH_RADIUS},
+  grid::{Grid, LineKind, Orientation},
+  mode::CurveMode,
+  model::{EditorModel, HandleSelection, ScreenPos, Selection},
+  slots::{Slot, SlotBuffer},
+  space::{self, Projection},
+};
+use splines::{Interpolation, Key};
+use std::fmt;
+
+use crate::vertex::{LineVertex, PointVertex, VColor, VPos, VRadius, VWidth};
+
+/// Maximum distance, in framebuffer pixels, between the drawn polyline and the actual curve.
+const FLATTEN_TOLERANCE: f32 = 0.25;
+/// Maximum number of grid lines per axis.
+const MAX_GRID_LINES: u32 = 100;
+
+/// Primitive restart index, separating line strips.
+const PRIM_RESTART_INDEX: u32 = u32::MAX;
+
+/// Factor applied to the colors of the channels that are not active.
+const INACTIVE_DIM: f32 = 0.5;
+/// Color of the segments that cannot be sampled, drawn as straight lines.
+const UNSAMPLED_COLOR: [f32; 3] = [0.8, 0.3, 0.3];
+/// Color of the handle lines.
+const HANDLE_COLOR: [f32; 3] = [0.4, 0.4, 0.4];
+/// Color of the rubber-band rectangle.
+const MARQUEE_COLOR: [f32; 3] = [0.8, 0.8, 0.3];
+
+/// Width of the curve, in window units.
+const CURVE_WIDTH: f32 = 2.5;
+/// Width of the handle lines, in window units.
+const HANDLE_WIDTH: f32 = 1.25;
+/// Width of the rubber-band rectangle, in window units.
+const MARQUEE_WIDTH: f32 = 1.;
+/// Width of the grid lines, in window units.
+const GRID_WIDTH: f32 = 1.;
+/// Width of the gradient strip, in window units.
+const GRADIENT_WIDTH: f32 = 24.;
+/// Vertical position of the gradient strip, in view space.
+const GRADIENT_Y: f32 = -0.9;
+/// Number of samples of the gradient strip.
+const GRADIENT_SAMPLES: usize = 256;
+/// Number of samples per segment of projected 3D paths.
+const PROJECTED_SAMPLES: usize = 32;
+/// Color of projected 3D paths.
+const PROJECTED_COLOR: [f32; 3] = [0.85, 0.85, 0.85];
+
+/// Editor view.
+///
+/// Points and lines live in persistent GPU buffers split into slots — one per key for points,
+/// one per curve segment and one per key’s handles for lines (see [`SlotBuffer`]). When the model
+/// changes, only the slots whose content actually changed are rewritten, through mapped slices,
+/// and the buffers are only reallocated when they run out of room.
+///
+/// All the visible channels are drawn, the inactive ones dimmed and without handles.
+pub struct EditorView {
+  // List of display points.
+  points: Tess<PointVertex, ()>,
+  // Content of the points, one slot per key: the key itself, then its handles and threshold marker.
+  // Slots of the channels are interleaved; see `point_item`.
+  point_slots: SlotBuffer<PointVertex>,
+  // List of lines.
+  lines: Tess<LineVertex, u32>,
+  // Vertices of the lines; see `line_item` for the slots.
+  line_slots: SlotBuffer<LineVertex>,
+  // Indices of the lines, one slot per slot of `line_slots`, each one ending with a restart.
+  index_slots: SlotBuffer<u32>,
+  // Vertex slot each index slot was built from.
+  indexed: Vec<Option<Slot>>,
+  // Keys around each curve segment of each channel and flattening parameters it was flattened
+  // with.
+  segments: Vec<Vec<SegmentInputs>>,
+  // Number of channels the slots were laid out for.
+  channel_nb: usize,
+  // Background grid lines.
+  grid: Tess<LineVertex, ()>,
+  // Revision of the model the point tessellation was built from, if any.
+  revision: Option<u64>,
+  // Revision of the model, camera scale and tolerance the lines were built from, if any.
+  lines_state: Option<(u64, Vector2<f32>, f32)>,
+  // Grid and visible area the grid tessellation was built from, if any.
+  grid_area: Option<(Grid, ScreenPos, ScreenPos)>,
+  // Strip showing the gradient, in gradient mode only.
+  gradient: Option<Tess<LineVertex, ()>>,
+  // Swatches of the color palette, in gradient mode only.
+  palette: Option<Tess<PointVertex, ()>>,
+  // Revision of the model and visible area the gradient and palette were built from, if any.
+  gradient_area: Option<(u64, ScreenPos, ScreenPos)>,
+  // Lines of the 3D path shown through a projection, replacing the curves, if any.
+  projected_lines: Option<Tess<LineVertex, ()>>,
+  // Keys of the 3D path shown through a projection, if any.
+  projected_points: Option<Tess<PointVertex, ()>>,
+  // Revision of the model the projected path was built from, if any.
+  projected_revision: Option<u64>,
+}
+
+/// Everything the flattening of a curve segment depends on.
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct SegmentInputs {
+  // Keys from the one before the segment to the one after it, as some interpolations look that
+  // far.
+  keys: [Option<Key<f32, ScreenPos>>; 4],
+  mode: CurveMode,
+  color: [f32; 3],
+  scale: Vector2<f32>,
+  tolerance: f32,
+}
+
+/// Items of the line slots.
+enum LineItem {
+  /// Rubber-band rectangle.
+  Marquee,
+  /// Curve segment starting at a key of a channel.
+  Segment(usize, usize),
+  /// Handles of a key of a channel.
+  Handles(usize, usize),
+}
+
+/// Slot of a line item, when drawing `channel_nb` channels.
+///
+/// Items of the channels are interleaved key by key, so that adding a key to a channel doesn’t
+/// move the items of the others.
+fn line_item(item: LineItem, channel_nb: usize) -> usize {
+  match item {
+    LineItem::Marquee => 0,
+    LineItem::Segment(c, i) => 1 + 2 * (i * channel_nb + c),
+    LineItem::Handles(c, i) => 2 + 2 * (i * channel_nb + c),
+  }
+}
+
+/// Slot of the points of a key of a channel, when drawing `channel_nb` channels.
+fn point_item(channel: usize, key: usize, channel_nb: usize) -> usize {
+  key * channel_nb + channel
+}
+
+/// Color of a channel, dimmed unless it’s the active one.
+fn channel_color(color: [f32; 3], active: bool) -> [f32; 3] {
+  if active {
+    color
+  } else {
+    color.map(|x| x * INACTIVE_DIM)
+  }
+}
+
+impl EditorView {
+  /// Create a default view.
+  pub fn new<C>(ctx: &mut C) -> Self
+  where
+    C: GraphicsContext<Backend = Backend>,
+  {
+    let point_slots = SlotBuffer::new(PointVertex::new(
+      VPos::new([0., 0.]),
+      VColor::new([0., 0., 0.]),
+      VRadius::new(0.),
+    ));
+    let line_slots = SlotBuffer::new(LineVertex::new(
+      VPos::new([0., 0.]),
+      VColor::new([0., 0., 0.]),
+      VWidth::new(0.),
+    ));
+    let index_slots = SlotBuffer::new(PRIM_RESTART_INDEX);
+    let points = Self::new_points(ctx, &point_slots).unwrap();
+    let lines = Self::new_lines(ctx, &line_slots, &index_slots).unwrap();
+    let indexed = Vec::new();
+    let segments = Vec::new();
+    let channel_nb = 0;
+    let grid = TessBuilder::new(ctx).set_vertex_nb(1).build().unwrap();
+    let revision = None;
+    let lines_state = None;
+    let grid_area = None;
+    let gradient = None;
+    let palette = None;
+    let gradient_area = None;
+    let projected_lines = None;
+    let projected_points = None;
+    let projected_revision = None;
+
+    EditorView {
+      points,
+      point_slots,
+      lines,
+      line_slots,
+      index_slots,
+      indexed,
+      segments,
+      channel_nb,
+      grid,
+      revision,
+      lines_state,
+      grid_area,
+      gradient,
+      palette,
+      gradient_area,
+      projected_lines,
+      projected_points,
+      projected_revision,
+    }
+  }
+
+  /// Allocate the point tessellation, with the capacity and content of its slots.
+  fn new_points<C>(
+    ctx: &mut C,
+    slots: &SlotBuffer<PointVertex>,
+  ) -> Result<Tess<PointVertex, ()>, TessError>
+  where
+    C: GraphicsContext<Backend = Backend>,
+  {
+    TessBuilder::new(ctx)
+      .set_mode(Mode::Point)
+      .set_vertices(slots.data().to_vec())
+      .build()
+  }
+
+  /// Allocate the line tessellation, with the capacity and content of its slots.
+  fn new_lines<C>(
+    ctx: &mut C,
+    vertices: &SlotBuffer<LineVertex>,
+    indices: &SlotBuffer<u32>,
+  ) -> Result<Tess<LineVertex, u32>, TessError>
+  where
+    C: GraphicsContext<Backend = Backend>,
+  {
+    TessBuilder::new(ctx)
+      .set_mode(Mode::LineStrip)
+      .set_vertices(vertices.data().to_vec())
+      .set_indices(indices.data().to_vec())
+      .set_primitive_restart_index(PRIM_RESTART_INDEX)
+      .build()
+  }
+
+  /// Update the lines: curve segments, handles and rubber-band rectangle.
+  ///
+  /// The curve is flattened so that it doesn’t deviate by more than `tolerance` in view space from
+  /// the actual curve, once mapped by `camera`. Only the segments whose surrounding keys changed
+  /// are flattened again.
+  fn update_lines<C>(
+    &mut self,
+    ctx: &mut C,
+    model: &EditorModel,
+    camera: &Camera,
+    tolerance: f32,
+  ) -> Result<(), ViewError>
+  where
+    C: GraphicsContext<Backend = Backend>,
+  {
+    let channel_nb = self.channel_nb;
+    let key_nb = self.key_nb(model);
+    let mode = model.mode();
+
+    for (c, channel) in model.channels().iter().enumerate() {
+      let active = c == model.active_channel();
+      let spline = channel.spline();
+      let keys = if channel.is_visible() {
+        spline.keys()
+      } else {
+        &[]
+      };
+      let segment_nb = keys.len().saturating_sub(1);
+      let color = channel_color(channel.color(), active);
+      let segments = &mut self.segments[c];
+
+      // curve segments
+      for i in 0..segment_nb {
+        let inputs = SegmentInputs {
+          keys: [
+            i.checked_sub(1).map(|i| keys[i]),
+            Some(keys[i]),
+            Some(keys[i + 1]),
+            keys.get(i + 2).copied(),
+          ],
+          mode,
+          color,
+          scale: camera.scale,
+          tolerance,
+        };
+
+        if segments.get(i) == Some(&inputs) {
+          continue;
+        }
+
+        // segments that cannot be sampled are shown as thin straight lines of a different color
+        let (points, color, width) = match flatten_segment(spline, mode, i, camera, tolerance) {
+          Some(points) => (points, color, CURVE_WIDTH),
+          None => (
+            segment_chord(spline, mode, i).map_or_else(Vec::new, |chord| chord.to_vec()),
+            UNSAMPLED_COLOR,
+            HANDLE_WIDTH,
+          ),
+        };
+        let vertices: Vec<_> = points
+          .into_iter()
+          .map(|p| LineVertex::new(VPos::new(p.into()), VColor::new(color), VWidth::new(width)))
+          .collect();
+        self
+          .line_slots
+          .set(line_item(LineItem::Segment(c, i), channel_nb), &vertices);
+
+        if i < segments.len() {
+          segments[i] = inputs;
+        } else {
+          segments.push(inputs);
+        }
+      }
+
+      // segments that don’t exist anymore
+      for i in segment_nb..segments.len() {
+        self
+          .line_slots
+          .clear(line_item(LineItem::Segment(c, i), channel_nb));
+      }
+
+      segments.truncate(segment_nb);
+
+      // handles of Bézier keys, only shown for the active channel
+      for (i, key) in keys.iter().enumerate() {
+        let handles = match key.interpolation {
+          _ if !active => Vec::new(),
+          Interpolation::Bezier(u) => vec![u, 2. * key.value - u],
+          Interpolation::StrokeBezier(input, output) => vec![input, key.value, output],
+          _ => Vec::new(),
+        };
+        let vertices: Vec<_> = handles
+          .into_iter()
+          .map(|p| {
+            LineVertex::new(
+              VPos::new(p.into()),
+              VColor::new(HANDLE_COLOR),
+              VWidth::new(HANDLE_WIDTH),
+            )
+          })
+          .collect();
+
+        self
+          .line_slots
+          .set(line_item(LineItem::Handles(c, i), channel_nb), &vertices);
+      }
+
+      // keys that don’t exist anymore, if other channels have more
+      for i in keys.len()..key_nb {
+        self
+          .line_slots
+          .clear(line_item(LineItem::Handles(c, i), channel_nb));
+      }
+    }
+
+    // rubber-band rectangle
+    let marquee: Vec<_> = match model.marquee() {
+      Some((a, b)) => [[a.x, a.y], [b.x, a.y], [b.x, b.y], [a.x, b.y], [a.x, a.y]]
+        .iter()
+        .map(|&corner| {
+          LineVertex::new(
+            VPos::new(corner),
+            VColor::new(MARQUEE_COLOR),
+            VWidth::new(MARQUEE_WIDTH),
+          )
+        })
+        .collect(),
+      None => Vec::new(),
+    };
+    self
+      .line_slots
+      .set(line_item(LineItem::Marquee, channel_nb), &marquee);
+
+    self
+      .line_slots
+      .truncate(line_item(LineItem::Segment(0, key_nb), channel_nb));
+    self.line_slots.compact();
+
+    // indices of the vertex slots that moved or changed length
+    let item_nb = self.line_slots.len();
+    self.indexed.resize(item_nb, None);
+
+    for item in 0..item_nb {
+      let slot = self.line_slots.slot(item);
+
+      if self.indexed[item] == slot {
+        continue;
+      }
+
+      let indices: Vec<_> = match slot {
+        Some(slot) if slot.len > 0 => (slot.start..slot.start + slot.len)
+          .map(|i| i as u32)
+          .chain(Some(PRIM_RESTART_INDEX))
+          .collect(),
+        _ => Vec::new(),
+      };
+
+      self.index_slots.set(item, &indices);
+      self.indexed[item] = slot;
+    }
+
+    self.index_slots.truncate(item_nb);
+    self.index_slots.compact();
+
+    // upload
+    let vertices_grown = self.line_slots.take_grown();
+    let indices_grown = self.index_slots.take_grown();
+
+    if vertices_grown || indices_grown {
+      self.lines = Self::new_lines(ctx, &self.line_slots, &self.index_slots)?;
+      self.line_slots.take_dirty();
+      self.index_slots.take_dirty();
+    } else {
+      let dirty = self.line_slots.take_dirty();
+
+      if !dirty.is_empty() {
+        let mut vertices = self.lines.vertices_mut()?;
+
+        for range in dirty {
+          vertices[range.clone()].copy_from_slice(&self.line_slots.data()[range]);
+        }
+      }
+
+      let dirty = self.index_slots.take_dirty();
+
+      if !dirty.is_empty() {
+        let mut indices = self.lines.indices_mut()?;
+
+        for range in dirty {
+          indices[range.clone()].copy_from_slice(&self.index_slots.data()[range]);
+        }
+      }
+    }
+
+    Ok(())
+  }
+
+  /// Update the points: keys of all the visible channels, then handles and threshold markers of
+  /// the active one.
+  fn update_points<C>(&mut self, ctx: &mut C, model: &EditorModel) -> Result<(), ViewError>
+  where
+    C: GraphicsContext<Backend = Backend>,
+  {
+    let channel_nb = self.channel_nb;
+    let key_nb = self.key_nb(model);
+
+    for (c, channel) in model.channels().iter().enumerate() {
+      let active = c == model.active_channel();
+      let keys = if channel.is_visible() {
+        channel.spline().keys()
+      } else {
+        &[]
+      };
+      let color = channel_color(channel.color(), active);
+
+      for (i, cp) in keys.iter().enumerate() {
+        let item = point_item(c, i, channel_nb);
+        let mut vertex = PointVertex::new(
+          VPos::new(cp.value.into()),
+          VColor::new(color),
+          VRadius::new(0.025 / 2.),
+        );
+
+        // other channels only show their keys
+        if !active {
+          self.point_slots.set(item, &[vertex]);
+          continue;
+        }
+
+        if model.is_selected(Selection::Key(i)) {
+          vertex.1 = VColor::new([1., 0.5, 0.5]);
+          vertex.2 = VRadius::new(0.025 / 2.);
+        }
+
+        let mut vertices = vec![vertex];
+
+        match cp.interpolation {
+          Interpolation::Bezier(mut u) => {
+            for _ in 0..2 {
+              let mut vertex = PointVertex::new(
+                VPos::new(u.into()),
+                VColor::new([0.5, 1., 0.5]),
+                VRadius::new(0.015 / 2.),
+              );
+
+              if model.is_selected(Selection::Handle(i, HandleSelection::Own))
+                || model.is_selected(Selection::Handle(i, HandleSelection::Mirror))
+              {
+                vertex.1 = VColor::new([1., 0.5, 0.5]);
+                vertex.2 = VRadius::new(0.015 / 2.);
+              }
+
+              vertices.push(vertex);
+
+              u = 2. * cp.value - u;
+            }
+          }
+
+          Interpolation::StrokeBezier(input, output) => {
+            // input
+            let mut vertex = PointVertex::new(
+              VPos::new(input.into()),
+              VColor::new([0.5, 1., 0.5]),
+              VRadius::new(0.015 / 2.),
+            );
+
+            if model.is_selected(Selection::Handle(i, HandleSelection::Own)) {
+              vertex.1 = VColor::new([1., 0.5, 0.5]);
+              vertex.2 = VRadius::new(0.015 / 2.);
+            }
+
+            vertices.push(vertex);
+
+            // output
+            let mut vertex = PointVertex::new(
+              VPos::new(output.into()),
+              VColor::new([0.5, 1., 0.5]),
+              VRadius::new(0.015 / 2.),
+            );
+
+            if model.is_selected(Selection::Handle(i, HandleSelection::Mirror)) {
+              vertex.1 = VColor::new([1., 0.5, 0.5]);
+              vertex.2 = VRadius::new(0.015 / 2.);
+            }
+
+            vertices.push(vertex);
+          }
+
+          _ => (),
+        }
+
+        // where step segments jump
+        if let Some(marker) = model.threshold_marker(i) {
+          let mut vertex = PointVertex::new(
+            VPos::new(marker.into()),
+            VColor::new([1., 0.8, 0.3]),
+            VRadius::new(0.015 / 2.),
+          );
+
+          if model.is_selected(Selection::Threshold(i)) {
+            vertex.1 = VColor::new([1., 0.5, 0.5]);
+          }
+
+          vertices.push(vertex);
+        }
+
+        self.point_slots.set(item, &vertices);
+      }
+
+      // keys that don’t exist anymore, if other channels have more
+      for i in keys.len()..key_nb {
+        self.point_slots.clear(point_item(c, i, channel_nb));
+      }
+    }
+
+    self.point_slots.truncate(key_nb * channel_nb);
+    self.point_slots.compact();
+
+    // upload
+    if self.point_slots.take_grown() {
+      self.points = Self::new_points(ctx, &self.point_slots)?;
+      self.point_slots.take_dirty();
+    } else {
+      let dirty = self.point_slots.take_dirty();
+
+      if !dirty.is_empty() {
+        let mut vertices = self.points.vertices_mut()?;
+
+        for range in dirty {
+          vertices[range.clone()].copy_from_slice(&self.point_slots.data()[range]);
+        }
+      }
+    }
+
+    Ok(())
+  }
+
+  /// Largest number of keys among the visible channels.
+  fn key_nb(&self, model: &EditorModel) -> usize {
+    model
+      .channels()
+      .iter()
+      .filter(|channel| channel.is_visible())
+      .map(|channel| channel.spline().len())
+      .max()
+      .unwrap_or(0)
+  }
+
+  /// Lay the slots out again for another number of channels, as the items of the channels are
+  /// interleaved.
+  fn relayout(&mut self, channel_nb: usize) {
+    self.point_slots.truncate(0);
+    self.line_slots.truncate(0);
+    self.segments = vec![Vec::new(); channel_nb];
+    self.channel_nb = channel_nb;
+    self.revision = None;
+    self.lines_state = None;
+  }
+
+  /// Rebuild tessellation of the grid lines crossing the area between `min` and `max`.
+  fn build_grid<C>(
+    &mut self,
+    ctx: &mut C,
+    grid: &Grid,
+    min: ScreenPos,
+    max: ScreenPos,
+  ) -> Result<(), TessError>
+  where
+    C: GraphicsContext<Backend = Backend>,
+  {
+    let mut vertices = Vec::new();
+
+    for line in grid.lines(min, max, MAX_GRID_LINES) {
+      let color = match line.kind {
+        LineKind::Minor => VColor::new([0.12, 0.12, 0.12]),
+        LineKind::Major => VColor::new([0.22, 0.22, 0.22]),
+        LineKind::Axis => VColor::new([0.4, 0.4, 0.4]),
+      };
+
+      let (a, b) = match line.orientation {
+        Orientation::Vertical => ([line.pos, min.y], [line.pos, max.y]),
+        Orientation::Horizontal => ([min.x, line.pos], [max.x, line.pos]),
+      };
+
+      let width = VWidth::new(GRID_WIDTH);
+      vertices.push(LineVertex::new(VPos::new(a), color, width));
+      vertices.push(LineVertex::new(VPos::new(b), color, width));
+    }
+
+    self.grid = TessBuilder::new(ctx)
+      .set_mode(Mode::Line)
+      .set_vertices(vertices)
+      .build()?;
+
+    Ok(())
+  }
+
+  /// Rebuild the tessellations of the gradient strip, along the bottom of the view, and of the
+  /// palette.
+  fn build_gradient<C>(
+    &mut self,
+    ctx: &mut C,
+    model: &EditorModel,
+    camera: &Camera,
+    aspect_ratio: f32,
+  ) -> Result<(), TessError>
+  where
+    C: GraphicsContext<Backend = Backend>,
+  {
+    let gradient = model.gradient();
+    let y = camera.from_view(ScreenPos::new(0., GRADIENT_Y)).y;
+
+    // opacity is shown by blending the colors with the black background
+    let vertices: Vec<_> = match gradient.range() {
+      Some((start, end)) if start < end => (0..GRADIENT_SAMPLES)
+        .filter_map(|i| {
+          let t = start + (end - start) * i as f32 / (GRADIENT_SAMPLES - 1) as f32;
+          let [r, g, b, a] = gradient.color(t)?;
+
+          Some(LineVertex::new(
+            VPos::new([t, y]),
+            VColor::new([r * a, g * a, b * a]),
+            VWidth::new(GRADIENT_WIDTH),
+          ))
+        })
+        .collect(),
+      _ => Vec::new(),
+    };
+
+    self.gradient = if vertices.len() < 2 {
+      None
+    } else {
+      Some(
+        TessBuilder::new(ctx)
+          .set_mode(Mode::LineStrip)
+          .set_vertices(vertices)
+          .build()?,
+      )
+    };
+
+    let swatches: Vec<_> = Palette::new(aspect_ratio)
+      .swatches()
+      .iter()
+      .map(|&(p, color)| {
+        PointVertex::new(
+          VPos::new(camera.from_view(p).into()),
+          VColor::new(color),
+          VRadius::new(SWATCH_RADIUS),
+        )
+      })
+      .collect();
+
+    self.palette = Some(
+      TessBuilder::new(ctx)
+        .set_mode(Mode::Point)
+        .set_vertices(swatches)
+        .build()?,
+    );
+
+    Ok(())
+  }
+
+  /// Rebuild the tessellations of the 3D path seen through a projection: the path itself, the
+  /// axes from the origin, the rubber-band rectangle and the keys of the active channel.
+  fn build_projected<C>(
+    &mut self,
+    ctx: &mut C,
+    model: &EditorModel,
+    projection: Projection,
+  ) -> Result<(), TessError>
+  where
+    C: GraphicsContext<Backend = Backend>,
+  {
+    let space = model.space();
+    let times = space.key_times();
+    let mut lines = Vec::new();
+    let mut push_line = |a: ScreenPos, b: ScreenPos, color: [f32; 3], width: f32| {
+      for &p in &[a, b] {
+        lines.push(LineVertex::new(
+          VPos::new(p.into()),
+          VColor::new(color),
+          VWidth::new(width),
+        ));
+      }
+    };
+
+    // points that cannot be projected break the path
+    let samples = times.windows(2).flat_map(|w| {
+      (0..PROJECTED_SAMPLES)
+        .map(move |j| w[0] + (w[1] - w[0]) * j as f32 / PROJECTED_SAMPLES as f32)
+    });
+    let points: Vec<_> = samples
+      .chain(times.last().copied())
+      .map(|t| projection.project(space.position(t)?))
+      .collect();
+
+    for w in points.windows(2) {
+      if let (Some(a), Some(b)) = (w[0], w[1]) {
+        push_line(a, b, PROJECTED_COLOR, CURVE_WIDTH);
+      }
+    }
+
+    let origin = Vector3::new(0., 0., 0.);
+
+    for (c, &(_, color)) in space::COMPONENTS.iter().enumerate() {
+      let mut axis = origin;
+      axis[c] = 1.;
+
+      if let (Some(a), Some(b)) = (projection.project(origin), projection.project(axis)) {
+        push_line(a, b, color, GRID_WIDTH);
+      }
+    }
+
+    if let Some((a, b)) = model.marquee() {
+      let corners = [a, ScreenPos::new(b.x, a.y), b, ScreenPos::new(a.x, b.y), a];
+
+      for w in corners.windows(2) {
+        push_line(w[0], w[1], MARQUEE_COLOR, MARQUEE_WIDTH);
+      }
+    }
+
+    let channel = &model.channels()[model.active_channel()];
+    let keys = (0..channel.spline().len()).filter_map(|i| {
+      let color = if model.is_selected(Selection::Key(i)) {
+        [1., 0.5, 0.5]
+      } else {
+        channel.color()
+      };
+
+      Some(PointVertex::new(
+        VPos::new(model.projected_key(i)?.into()),
+        VColor::new(color),
+        VRadius::new(0.025 / 2.),
+      ))
+    });
+    let points: Vec<_> = if channel.is_visible() {
+      keys.collect()
+    } else {
+      Vec::new()
+    };
+
+    self.projected_lines = Self::build_optional(ctx, Mode::Line, lines)?;
+    self.projected_points = Self::build_optional(ctx, Mode::Point, points)?;
+
+    Ok(())
+  }
+
+  /// Build a tessellation out of some vertices, if there are any.
+  fn build_optional<C, V>(
+    ctx: &mut C,
+    mode: Mode,
+    vertices: Vec<V>,
+  ) -> Result<Option<Tess<V, ()>>, TessError>
+  where
+    C: GraphicsContext<Backend = Backend>,
+    V: luminance::vertex::Vertex,
+  {
+    if vertices.is_empty() {
+      return Ok(None);
+    }
+
+    TessBuilder::new(ctx)
+      .set_mode(mode)
+      .set_vertices(vertices)
+      .build()
+      .map(Some)
+  }
+
+  /// Update the tessellations if the model or the visible area have changed since the last update.
+  pub fn update_if_needed<C>(
+    &mut self,
+    surface: &mut C,
+    model: &EditorModel,
+    camera: &Camera,
+    viewport: &Viewport,
+  ) -> Result<(), ViewError>
+  where
+    C: GraphicsContext<Backend = Backend>,
+  {
+    if self.channel_nb != model.channels().len() {
+      self.relayout(model.channels().len());
+    }
+
+    if self.revision != Some(model.revision()) {
+      self.revision = Some(model.revision());
+      self.update_points(surface, model)?;
+    }
+
+    // the flattening depends on the zoom level, but not on panning
+    let tolerance = FLATTEN_TOLERANCE * 2. / viewport.framebuffer_size().y;
+    let lines_state = Some((model.revision(), camera.scale, tolerance));
+
+    if self.lines_state != lines_state {
+      self.lines_state = lines_state;
+      self.update_lines(surface, model, camera, tolerance)?;
+    }
+
+    let aspect_ratio = viewport.aspect_ratio();
+    let min = camera.from_view(ScreenPos::new(-aspect_ratio, -1.));
+    let max = camera.from_view(ScreenPos::new(aspect_ratio, 1.));
+    let grid_area = Some((*model.grid(), min, max));
+
+    if self.grid_area != grid_area {
+      self.grid_area = grid_area;
+      self.build_grid(surface, model.grid(), min, max)?;
+    }
+
+    if model.mode() == CurveMode::Gradient {
+      let gradient_area = Some((model.revision(), min, max));
+
+      if self.gradient_area != gradient_area {
+        self.gradient_area = gradient_area;
+        self.build_gradient(surface, model, camera, aspect_ratio)?;
+      }
+    } else if self.gradient_area.is_some() {
+      self.gradient_area = None;
+      self.gradient = None;
+      self.palette = None;
+    }
+
+    match model.projection() {
+      Some(projection) if self.projected_revision != Some(model.revision()) => {
+        self.projected_revision = Some(model.revision());
+        self.build_projected(surface, model, projection)?;
+      }
+
+      Some(_) => (),
+
+      None => {
+        self.projected_revision = None;
+        self.projected_lines = None;
+        self.projected_points = None;
+      }
+    }
+
+    Ok(())
+  }
+
+  /// Get the underlying grid tessellation.
+  pub fn grid(&self) -> &Tess<LineVertex, ()> {
+    &self.grid
+  }
+
+  /// Get the tessellation of the gradient strip, if there is one to show.
+  pub fn gradient(&self) -> Option<&Tess<LineVertex, ()>> {
+    self.gradient.as_ref()
+  }
+
+  /// Get the tessellation of the palette swatches, if the palette is shown.
+  pub fn palette(&self) -> Option<&Tess<PointVertex, ()>> {
+    self.palette.as_ref()
+  }
+
+  /// Get the tessellation of the lines of the projected 3D path, if any.
+  pub fn projected_lines(&self) -> Option<&Tess<LineVertex, ()>> {
+    self.projected_lines.as_ref()
+  }
+
+  /// Get the tessellation of the keys of the projected 3D path, if any.
+  pub fn projected_points(&self) -> Option<&Tess<PointVertex, ()>> {
+    self.projected_points.as_ref()
+  }
+
+  /// Get the used part of the point tessellation.
+  pub fn points(&self) -> TessView<'_, PointVertex, (), (), Interleaved> {
+    // slots never extend past the capacity of the tessellation
+    TessView::sub(&self.points, self.point_slots.used()).unwrap()
+  }
+
+  /// Get the used part of the line tessellation.
+  pub fn lines(&self) -> TessView<'_, LineVertex, u32, (), Interleaved> {
+    TessView::sub(&self.lines, self.index_slots.used()).unwrap()
+  }
+}
+
+/// Possible errors that might occur while updating the view.
+#[derive(Debug)]
+pub enum ViewError {
+  /// A tessellation couldn’t be created.
+  TessError(TessError),
+  /// A tessellation couldn’t be mapped for update.
+  TessMapError(TessMapError),
+}
+
+impl From<TessError> for ViewError {
+  fn from(e: TessError) -> Self {
+    ViewError::TessError(e)
+  }
+}
+
+impl From<TessMapError> for ViewError {
+  fn from(e: TessMapError) -> Self {
+    ViewError::TessMapError(e)
+  }
+}
+
+impl fmt::Display for ViewError {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    match *self {
+      ViewError::TessError(ref e) => write!(f, "cannot create tessellation: {}", e),
+      ViewError::TessMapError(ref e) => write!(f, "cannot map tessellation: {}", e),
+    }
+  }
+}
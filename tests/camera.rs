@@ -0,0 +1,79 @@
+mod common;
+
+use cgmath::Vector2;
+use common::assert_close;
+use spline_editor::{
+  camera::{Camera, MAX_SCALE, MIN_SCALE},
+  model::ScreenPos,
+};
+
+#[test]
+fn view_and_curve_spaces_round_trip() {
+  let mut camera = Camera {
+    center: ScreenPos::new(3., -2.),
+    scale: Vector2::new(0.25, 4.),
+  };
+  let p = ScreenPos::new(1.5, 0.75);
+
+  assert_close(camera.to_view(camera.center), ScreenPos::new(0., 0.));
+  assert_close(camera.from_view(camera.to_view(p)), p);
+  assert_close(camera.to_view(camera.from_view(p)), p);
+
+  // panning moves the content along in view space
+  let before = camera.to_view(p);
+  camera.pan(ScreenPos::new(0.5, -0.25));
+  assert_close(camera.to_view(p), before + ScreenPos::new(0.5, -0.25));
+}
+
+#[test]
+fn zoom_around_the_cursor() {
+  let mut camera = Camera::default();
+  let cursor = ScreenPos::new(0.6, -0.3);
+  let under_cursor = camera.from_view(cursor);
+
+  camera.zoom(4., cursor);
+  assert_close(camera.from_view(cursor), under_cursor);
+  assert!(camera.scale.x > 2. && camera.scale.y > 1.);
+
+  camera.zoom(-7., cursor);
+  assert_close(camera.from_view(cursor), under_cursor);
+  assert!(camera.scale.x < 2. && camera.scale.y < 1.);
+}
+
+#[test]
+fn zoom_is_clamped() {
+  let mut camera = Camera::default();
+  let cursor = ScreenPos::new(-0.5, 0.5);
+  let under_cursor = camera.from_view(cursor);
+
+  camera.zoom(1000., cursor);
+  assert_eq!(camera.scale, Vector2::new(MAX_SCALE, MAX_SCALE));
+  assert_close(camera.from_view(cursor), under_cursor);
+
+  camera.zoom(-2000., cursor);
+  assert_eq!(camera.scale, Vector2::new(MIN_SCALE, MIN_SCALE));
+  assert_close(camera.from_view(cursor), under_cursor);
+
+  // zooming back in starts right away
+  camera.zoom(1., cursor);
+  assert!(camera.scale.x > MIN_SCALE);
+}
+
+#[test]
+fn frame_is_clamped() {
+  let mut camera = Camera::default();
+
+  // a single key, then a flat selection barely wider than rounding errors
+  let p = ScreenPos::new(2., 3.);
+  camera.frame(p, p, 1.5);
+  assert!(camera.scale.x.is_finite() && camera.scale.y.is_finite());
+  assert_close(camera.to_view(p), ScreenPos::new(0., 0.));
+
+  camera.frame(p, p + ScreenPos::new(1e-6, 2e-7), 1.5);
+  assert!(camera.scale.x <= MAX_SCALE && camera.scale.y <= MAX_SCALE);
+  assert_close(camera.from_view(camera.to_view(p)), p);
+
+  // and a huge one
+  camera.frame(-p * 1e6, p * 1e6, 1.5);
+  assert_eq!(camera.scale, Vector2::new(MIN_SCALE, MIN_SCALE));
+}
@@ -0,0 +1,72 @@
+//! Helpers shared by the integration tests.
+
+// each test crate only uses some of them
+#![allow(dead_code)]
+
+use cgmath::InnerSpace;
+use spline_editor::model::ScreenPos;
+use splines::Spline;
+use std::fmt::Debug;
+
+/// Largest difference between values considered equal.
+pub const TOLERANCE: f32 = 1e-5;
+
+/// Check that two positions or vectors are the same, up to rounding errors.
+pub fn assert_close<V>(a: V, b: V)
+where
+  V: InnerSpace<Scalar = f32> + Debug,
+{
+  assert!((a - b).magnitude() < TOLERANCE, "{:?} != {:?}", a, b);
+}
+
+/// Sample a spline densely over the time range of its keys.
+pub fn dense_samples(spline: &Spline<f32, ScreenPos>) -> Vec<Option<ScreenPos>> {
+  let keys = spline.keys();
+  let (t0, t1) = (keys[0].t, keys[keys.len() - 1].t);
+
+  (0..=200)
+    .map(|i| spline.sample(t0 + (t1 - t0) * i as f32 / 200.))
+    .collect()
+}
+
+/// Sample a function curve densely over the time range of its keys, as points of its graph: the
+/// horizontal coordinate of each point is its time.
+///
+/// Unlike [`dense_samples`], this doesn’t depend on the horizontal coordinate of values, which
+/// step segments take from their keys.
+pub fn graph_samples(spline: &Spline<f32, ScreenPos>) -> Vec<Option<ScreenPos>> {
+  let keys = spline.keys();
+  let (t0, t1) = (keys[0].t, keys[keys.len() - 1].t);
+
+  (0..=200)
+    .map(|i| {
+      let t = t0 + (t1 - t0) * i as f32 / 200.;
+      spline.sample(t).map(|p| ScreenPos::new(t, p.y))
+    })
+    .collect()
+}
+
+/// Check that two curves sampled with [`dense_samples`] or [`graph_samples`] are the same.
+pub fn assert_same_curve(before: &[Option<ScreenPos>], after: &[Option<ScreenPos>]) {
+  assert_eq!(before.len(), after.len());
+
+  for (a, b) in before.iter().zip(after) {
+    match (a, b) {
+      (&Some(a), &Some(b)) => assert_close(a, b),
+      _ => assert_eq!(a, b),
+    }
+  }
+}
+
+/// Check that two function curves sampled with [`dense_samples`] are the same; only the vertical
+/// coordinate of their values matters.
+pub fn assert_same_function(before: &[Option<ScreenPos>], after: &[Option<ScreenPos>]) {
+  assert_eq!(before.len(), after.len());
+
+  for (a, b) in before.iter().zip(after) {
+    match (a, b) {
+      (Some(a), Some(b)) => assert!((a - b).y.abs() < TOLERANCE, "{:?} != {:?}", a, b),
+      _ => assert_eq!(a, b),
+    }
+  }
+}
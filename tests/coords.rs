@@ -0,0 +1,102 @@
+mod common;
+
+use common::assert_close;
+use spline_editor::{camera::Camera, coords::Viewport, model::ScreenPos};
+
+#[test]
+fn window_corners_to_ndc() {
+  let viewport = Viewport::new([800, 600], [800, 600]);
+
+  assert_close(
+    viewport.framebuffer_to_ndc(viewport.window_to_framebuffer(ScreenPos::new(0., 0.))),
+    ScreenPos::new(-1., 1.),
+  );
+  assert_close(
+    viewport.framebuffer_to_ndc(viewport.window_to_framebuffer(ScreenPos::new(800., 600.))),
+    ScreenPos::new(1., -1.),
+  );
+  assert_close(
+    viewport.framebuffer_to_ndc(viewport.window_to_framebuffer(ScreenPos::new(400., 300.))),
+    ScreenPos::new(0., 0.),
+  );
+}
+
+#[test]
+fn content_scale() {
+  // HiDPI: the framebuffer has twice as many pixels as the window has screen coordinates
+  let viewport = Viewport::new([800, 600], [1600, 1200]);
+
+  assert_eq!(viewport.content_scale(), ScreenPos::new(2., 2.));
+  assert_close(
+    viewport.window_to_framebuffer(ScreenPos::new(400., 300.)),
+    ScreenPos::new(800., 600.),
+  );
+
+  // the cursor in the bottom-right corner of the window is in the bottom-right corner of the view
+  assert_close(
+    viewport.window_to_view(ScreenPos::new(800., 600.)),
+    ScreenPos::new(4. / 3., -1.),
+  );
+}
+
+#[test]
+fn view_space_is_isotropic() {
+  let viewport = Viewport::new([1000, 500], [1000, 500]);
+
+  // 100 screen units are the same view-space length on both axes
+  let o = viewport.window_to_view(ScreenPos::new(500., 250.));
+  let x = viewport.window_to_view(ScreenPos::new(600., 250.));
+  let y = viewport.window_to_view(ScreenPos::new(500., 150.));
+
+  assert!(((x - o).x - (y - o).y).abs() < 1e-6);
+}
+
+#[test]
+fn resize() {
+  let mut viewport = Viewport::new([800, 600], [800, 600]);
+  let camera = Camera::default();
+  let p = ScreenPos::new(0.25, 0.5);
+
+  let before = viewport.curve_to_window(&camera, p);
+  assert_close(viewport.window_to_curve(&camera, before), p);
+
+  // widening the window keeps the vertical mapping and reveals more content horizontally
+  viewport.resize_window([1600, 600]);
+  viewport.resize_framebuffer([1600, 600]);
+  assert_eq!(viewport.aspect_ratio(), 16. / 6.);
+
+  let after = viewport.curve_to_window(&camera, p);
+  assert_close(viewport.window_to_curve(&camera, after), p);
+  assert!((after.y - before.y).abs() < 1e-4);
+  assert!((after.x - 800. - (before.x - 400.)).abs() < 1e-3);
+
+  // a minimized window doesn’t break the mapping
+  viewport.resize_window([0, 0]);
+  viewport.resize_framebuffer([0, 0]);
+  assert!(viewport
+    .window_to_view(ScreenPos::new(0., 0.))
+    .x
+    .is_finite());
+}
+
+#[test]
+fn zoom_around_cursor() {
+  let viewport = Viewport::new([800, 600], [800, 600]);
+  let mut camera = Camera::default();
+  let cursor = ScreenPos::new(200., 100.);
+  let under_cursor = viewport.window_to_curve(&camera, cursor);
+
+  camera.zoom(3., viewport.window_to_view(cursor));
+  assert_close(viewport.window_to_curve(&camera, cursor), under_cursor);
+}
+
+#[test]
+fn pixel_size_in_view_space() {
+  // HiDPI: two framebuffer pixels per window unit
+  let viewport = Viewport::new([800, 600], [1600, 1200]);
+  let a = viewport.window_to_view(ScreenPos::new(400., 300.));
+  let b = viewport.window_to_view(ScreenPos::new(400., 301.));
+
+  assert!((viewport.pixel_size() - (a - b).y.abs()).abs() < 1e-6);
+  assert!((viewport.pixel_size() - 2. / 600.).abs() < 1e-6);
+}
@@ -0,0 +1,108 @@
+use spline_editor::{
+  export::{write_spline, ExportError, ExportFormat, Sampling, Table, BINARY_MAGIC, MAX_SAMPLES},
+  model::ScreenPos,
+};
+use splines::{Interpolation, Key, Spline};
+
+fn ramp() -> Spline<f32, ScreenPos> {
+  Spline::from_vec(vec![
+    Key::new(0., ScreenPos::new(0., 0.), Interpolation::Linear),
+    Key::new(2., ScreenPos::new(2., 1.), Interpolation::Linear),
+  ])
+}
+
+#[test]
+fn bake_count_and_rate() {
+  let table = Table::bake(&ramp(), Sampling::Count(5)).unwrap();
+  assert_eq!(table.values, vec![0., 0.25, 0.5, 0.75, 1.]);
+  assert_eq!((table.start, table.end, table.step()), (0., 2., 0.5));
+
+  // the last sample is the last one fitting in the curve
+  let table = Table::bake(&ramp(), Sampling::Rate(1.5)).unwrap();
+  assert_eq!(table.values.len(), 4);
+  assert!((table.end - 2.).abs() < 1e-6);
+
+  assert!(matches!(
+    Table::bake(&ramp(), Sampling::Count(1)),
+    Err(ExportError::InvalidSampling(_))
+  ));
+  assert!(matches!(
+    Table::bake(&ramp(), Sampling::Count(MAX_SAMPLES + 1)),
+    Err(ExportError::InvalidSampling(_))
+  ));
+  assert!(matches!(
+    Table::bake(&Spline::from_vec(Vec::new()), Sampling::Count(8)),
+    Err(ExportError::EmptySpline)
+  ));
+}
+
+#[test]
+fn reject_unbounded_rates() {
+  // as given by `--rate inf`
+  let inf = "inf".parse().unwrap();
+
+  for &rate in &[inf, f32::NAN, 1e30, MAX_SAMPLES as f32 / 2.] {
+    assert!(
+      matches!(
+        Table::bake(&ramp(), Sampling::Rate(rate)),
+        Err(ExportError::InvalidSampling(_))
+      ),
+      "{}",
+      rate
+    );
+  }
+
+  // the largest rate fitting in the curve
+  let rate = (MAX_SAMPLES - 1) as f32 / 2.;
+  let table = Table::bake(&ramp(), Sampling::Rate(rate)).unwrap();
+  assert_eq!(table.values.len(), MAX_SAMPLES);
+}
+
+#[test]
+fn write_formats() {
+  let table = Table::bake(&ramp(), Sampling::Count(3)).unwrap();
+  let write = |format| {
+    let mut out = Vec::new();
+    table.write(&mut out, format, "RAMP").unwrap();
+    out
+  };
+
+  let csv = String::from_utf8(write(ExportFormat::Csv)).unwrap();
+  assert_eq!(csv, "t,value\n0,0\n1,0.5\n2,1\n");
+
+  let bin = write(ExportFormat::Binary);
+  assert_eq!(bin.len(), 20 + 3 * 4);
+  assert_eq!(bin[0..4], BINARY_MAGIC);
+  assert_eq!(bin[8..12], 3u32.to_le_bytes());
+  assert_eq!(bin[16..20], 2f32.to_le_bytes());
+  assert_eq!(bin[24..28], 0.5f32.to_le_bytes());
+
+  let rust = String::from_utf8(write(ExportFormat::Rust)).unwrap();
+  assert!(rust.contains("pub const RAMP: [f32; 3] = [\n  0.0,\n  0.5,\n  1.0,\n];"));
+
+  let glsl = String::from_utf8(write(ExportFormat::Glsl)).unwrap();
+  assert!(glsl.contains("const float RAMP[3] = float[3](\n  0.0,\n  0.5,\n  1.0\n);"));
+}
+
+#[test]
+fn write_scalar_splines() {
+  let spline = Spline::from_vec(vec![
+    Key::new(
+      0.,
+      ScreenPos::new(0., 1.),
+      Interpolation::Bezier(ScreenPos::new(0.5, 2.)),
+    ),
+    Key::new(1.5, ScreenPos::new(1.5, 0.), Interpolation::Step(0.25)),
+  ]);
+  let mut out = Vec::new();
+  write_spline(&spline, &mut out).unwrap();
+
+  let scalar: Spline<f32, f32> = serde_json::from_slice(&out).unwrap();
+  assert_eq!(
+    scalar.keys(),
+    &[
+      Key::new(0., 1., Interpolation::Bezier(2.)),
+      Key::new(1.5, 0., Interpolation::Step(0.25)),
+    ]
+  );
+}
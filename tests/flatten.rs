@@ -0,0 +1,185 @@
+use cgmath::{InnerSpace as _, Vector2};
+use spline_editor::{
+  camera::Camera,
+  flatten::{flatten, flatten_segment, segment_chord, segment_point},
+  mode::CurveMode,
+  model::ScreenPos,
+};
+use splines::{Interpolation, Key, Spline};
+
+const TOLERANCE: f32 = 0.002;
+
+fn test_spline() -> Spline<f32, ScreenPos> {
+  Spline::from_vec(vec![
+    Key::new(0., ScreenPos::new(0., 0.), Interpolation::Linear),
+    Key::new(1., ScreenPos::new(1., 1.), Interpolation::Cosine),
+    // tight Bézier bend
+    Key::new(
+      2.,
+      ScreenPos::new(2., -1.),
+      Interpolation::Bezier(ScreenPos::new(2.5, 3.)),
+    ),
+    Key::new(
+      2.2,
+      ScreenPos::new(2.2, 0.),
+      Interpolation::StrokeBezier(ScreenPos::new(1.5, -2.), ScreenPos::new(2.3, 0.)),
+    ),
+    // long, slightly curved part
+    Key::new(10., ScreenPos::new(10., 0.5), Interpolation::Cosine),
+    Key::new(10.37, ScreenPos::new(10.37, 0.6), Interpolation::Linear),
+  ])
+}
+
+/// Largest distance, in view space, between the curve sampled densely and the polyline.
+fn max_deviation(spline: &Spline<f32, ScreenPos>, camera: &Camera, polyline: &[ScreenPos]) -> f32 {
+  let keys = spline.keys();
+  let polyline: Vec<_> = polyline.iter().map(|&p| camera.to_view(p)).collect();
+  let mut max = 0f32;
+
+  for i in 0..keys.len() - 1 {
+    for j in 0..=1000 {
+      let t = keys[i].t + (keys[i + 1].t - keys[i].t) * j as f32 / 1000.;
+      let p = camera.to_view(segment_point(spline, CurveMode::Function, i, t).unwrap());
+
+      let d = polyline
+        .windows(2)
+        .map(|w| {
+          let ab = w[1] - w[0];
+          let k = ((p - w[0]).dot(ab) / ab.magnitude2().max(1e-12)).clamp(0., 1.);
+          (p - (w[0] + ab * k)).magnitude()
+        })
+        .fold(f32::INFINITY, f32::min);
+
+      max = max.max(d);
+    }
+  }
+
+  max
+}
+
+#[test]
+fn polyline_follows_curve() {
+  let spline = test_spline();
+
+  for &scale in &[
+    Vector2::new(0.2, 0.5),
+    Vector2::new(2., 1.),
+    Vector2::new(20., 30.),
+  ] {
+    let camera = Camera {
+      center: ScreenPos::new(5., 0.),
+      scale,
+    };
+    let polylines = flatten(&spline, CurveMode::Function, &camera, TOLERANCE);
+
+    assert_eq!(polylines.len(), 1);
+    assert!(max_deviation(&spline, &camera, &polylines[0]) <= TOLERANCE * 1.5);
+  }
+}
+
+#[test]
+fn polyline_ends_on_last_key() {
+  let spline = test_spline();
+  let polylines = flatten(&spline, CurveMode::Function, &Camera::default(), TOLERANCE);
+  let last = polylines[0].last().unwrap();
+
+  // the last key isn’t a multiple of any time step
+  assert!((last - ScreenPos::new(10.37, 0.6)).magnitude() < 1e-6);
+}
+
+#[test]
+fn vertices_adapt_to_zoom() {
+  let spline = test_spline();
+  let count = |scale| {
+    let camera = Camera {
+      center: ScreenPos::new(0., 0.),
+      scale,
+    };
+    flatten(&spline, CurveMode::Function, &camera, TOLERANCE)[0].len()
+  };
+
+  // far fewer vertices than stepping every 0.01
+  assert!(count(Vector2::new(0.2, 0.2)) < 200);
+  assert!(count(Vector2::new(20., 20.)) > count(Vector2::new(0.2, 0.2)));
+}
+
+#[test]
+fn steps_and_gaps() {
+  let spline = Spline::from_vec(vec![
+    Key::new(0., ScreenPos::new(0., 1.), Interpolation::CatmullRom),
+    Key::new(1., ScreenPos::new(1., 0.), Interpolation::Step(0.5)),
+    Key::new(2., ScreenPos::new(2., 1.), Interpolation::Linear),
+    Key::new(3., ScreenPos::new(3., 0.), Interpolation::Linear),
+  ]);
+  let polylines = flatten(&spline, CurveMode::Function, &Camera::default(), TOLERANCE);
+
+  // the Catmull-Rom segment lacks a neighbour and cannot be drawn
+  assert_eq!(polylines.len(), 1);
+  assert_eq!(
+    polylines[0][..4],
+    [
+      ScreenPos::new(1., 0.),
+      ScreenPos::new(1.5, 0.),
+      ScreenPos::new(1.5, 1.),
+      ScreenPos::new(2., 1.),
+    ]
+  );
+  assert_eq!(polylines[0].last(), Some(&ScreenPos::new(3., 0.)));
+}
+
+#[test]
+fn catmull_rom_needs_neighbours() {
+  let spline = Spline::from_vec(vec![
+    Key::new(0., ScreenPos::new(0., 0.), Interpolation::CatmullRom),
+    Key::new(1., ScreenPos::new(1., 1.), Interpolation::CatmullRom),
+    Key::new(2., ScreenPos::new(2., 0.), Interpolation::CatmullRom),
+    Key::new(3., ScreenPos::new(3., 1.), Interpolation::CatmullRom),
+  ]);
+  let camera = Camera::default();
+
+  // only the middle segment has a key on both sides
+  assert!(flatten_segment(&spline, CurveMode::Function, 0, &camera, TOLERANCE).is_none());
+  assert!(flatten_segment(&spline, CurveMode::Function, 2, &camera, TOLERANCE).is_none());
+
+  let middle = flatten_segment(&spline, CurveMode::Function, 1, &camera, TOLERANCE).unwrap();
+  assert_eq!(middle.first(), Some(&ScreenPos::new(1., 1.)));
+  assert_eq!(middle.last(), Some(&ScreenPos::new(2., 0.)));
+
+  // the others can still be shown as straight lines
+  assert_eq!(
+    segment_chord(&spline, CurveMode::Function, 0),
+    Some([ScreenPos::new(0., 0.), ScreenPos::new(1., 1.)])
+  );
+  assert_eq!(
+    segment_chord(&spline, CurveMode::Function, 2),
+    Some([ScreenPos::new(2., 0.), ScreenPos::new(3., 1.)])
+  );
+  assert_eq!(segment_chord(&spline, CurveMode::Function, 3), None);
+}
+
+#[test]
+fn paths_follow_values() {
+  let spline = Spline::from_vec(vec![
+    Key::new(0., ScreenPos::new(2., 0.), Interpolation::Step(0.5)),
+    Key::new(1., ScreenPos::new(0., 0.), Interpolation::Linear),
+    Key::new(2., ScreenPos::new(0., 1.), Interpolation::Linear),
+  ]);
+  let polylines = flatten(&spline, CurveMode::Path, &Camera::default(), TOLERANCE);
+
+  // the step jumps straight to the next key, going back left
+  assert_eq!(polylines.len(), 1);
+  assert_eq!(
+    polylines[0][..2],
+    [ScreenPos::new(2., 0.), ScreenPos::new(0., 0.)]
+  );
+  assert!(polylines[0][1..].iter().all(|p| p.x == 0.));
+  assert_eq!(polylines[0].last(), Some(&ScreenPos::new(0., 1.)));
+  assert_eq!(
+    segment_point(&spline, CurveMode::Path, 1, 1.5),
+    Some(ScreenPos::new(0., 0.5))
+  );
+  assert_eq!(
+    segment_point(&spline, CurveMode::Function, 1, 1.5),
+    Some(ScreenPos::new(1.5, 0.5))
+  );
+}
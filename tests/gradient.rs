@@ -0,0 +1,194 @@
+use spline_editor::{
+  document::{Document, DOCUMENT_VERSION},
+  export::{Ramp, Sampling, RAMP_MAGIC},
+  gradient::{self, Palette, COMPONENTS},
+  mode::CurveMode,
+  model::{EditorError, EditorModel, ScreenPos},
+};
+use splines::Interpolation;
+
+fn assert_color(model: &EditorModel, t: f32, expected: [f32; 4]) {
+  let color = model.gradient().color(t).unwrap();
+  assert!(
+    color
+      .iter()
+      .zip(&expected)
+      .all(|(a, b)| (a - b).abs() < 1e-5),
+    "{:?} != {:?} at t = {}",
+    color,
+    expected,
+    t
+  );
+}
+
+/// Gradient from red at 0 to half-transparent blue at 1.
+fn red_to_blue() -> EditorModel {
+  let mut model = EditorModel::new();
+  model.set_mode(CurveMode::Gradient);
+  model.set_color(0., [1., 0., 0., 1.]).unwrap();
+  model.set_color(1., [0., 0., 1., 0.5]).unwrap();
+  model
+}
+
+#[test]
+fn gradient_mode_adds_component_channels() {
+  let mut model = EditorModel::new();
+  model.set_mode(CurveMode::Gradient);
+
+  let names: Vec<_> = model.channels().iter().map(|c| c.name()).collect();
+  assert_eq!(names, vec!["curve", "red", "green", "blue", "alpha"]);
+  assert_eq!(model.active_channel(), 0);
+  assert_eq!(model.gradient().color(0.), Some(gradient::DEFAULT_COLOR));
+
+  // a single undo goes back to the previous mode and channels
+  assert!(model.undo());
+  assert_eq!(model.mode(), CurveMode::Function);
+  assert_eq!(model.channels().len(), 1);
+}
+
+#[test]
+fn set_color_keys_all_components() {
+  let mut model = red_to_blue();
+
+  for &(name, _) in &COMPONENTS {
+    let channel = model.channels().iter().find(|c| c.name() == name).unwrap();
+    let times: Vec<_> = channel.spline().keys().iter().map(|k| k.t).collect();
+    assert_eq!(times, vec![0., 1.], "{}", name);
+  }
+
+  assert_color(&model, 0.5, [0.5, 0., 0.5, 0.75]);
+  assert_eq!(gradient::hex([1., 0.5, 0., 1.]), "#ff8000ff");
+
+  // keys in the middle are inserted on the curves, and existing ones are reused
+  model.set_color(0.5, [0., 1., 0., 1.]).unwrap();
+  model.set_color(1., [0., 0., 1., 1.]).unwrap();
+  assert_color(&model, 0.5, [0., 1., 0., 1.]);
+  assert_color(&model, 1., [0., 0., 1., 1.]);
+  assert_eq!(model.gradient().range(), Some((0., 1.)));
+  assert_eq!(model.channels()[1].spline().len(), 3);
+
+  // each color is a single history entry
+  assert!(model.undo());
+  assert_color(&model, 1., [0., 0., 1., 0.5]);
+}
+
+#[test]
+fn rejected_colors_change_nothing() {
+  let mut model = red_to_blue();
+  let before: Vec<_> = model
+    .channels()
+    .iter()
+    .map(|c| c.spline().keys().to_vec())
+    .collect();
+
+  // red could be set, but green cannot
+  assert!(matches!(
+    model.set_color(0.5, [1., f32::NAN, 0., 1.]),
+    Err(EditorError::InvalidValue(_))
+  ));
+  let after: Vec<_> = model
+    .channels()
+    .iter()
+    .map(|c| c.spline().keys().to_vec())
+    .collect();
+  assert_eq!(after, before);
+
+  // nothing was recorded either: undoing goes back before the last color
+  assert!(model.undo());
+  assert_eq!(model.gradient().range(), Some((0., 0.)));
+}
+
+#[test]
+fn pick_colors_for_selected_keys() {
+  let mut model = red_to_blue();
+  let red = model.channels().iter().position(|c| c.name() == "red");
+  model.set_active_channel(red.unwrap()).unwrap();
+  model.select_in_rect(ScreenPos::new(0.5, -1.), ScreenPos::new(1.5, 2.), false);
+  assert_eq!(model.selected_keys(), vec![1]);
+
+  // opacity is kept, and so is the selection
+  model.pick_color([1., 1., 0.]).unwrap();
+  assert_color(&model, 1., [1., 1., 0., 0.5]);
+  assert_color(&model, 0., [1., 0., 0., 1.]);
+  assert_eq!(model.selected_keys(), vec![1]);
+}
+
+#[test]
+fn keep_the_selection_of_components_with_more_keys() {
+  let mut model = red_to_blue();
+  let red = model.channels().iter().position(|c| c.name() == "red");
+  model.set_active_channel(red.unwrap()).unwrap();
+
+  // red gets more keys than the other components
+  for &t in &[0.25, 0.5, 0.75] {
+    model.add_point(ScreenPos::new(t, 0.5), Interpolation::Linear);
+  }
+  model.select_all();
+  assert_eq!(model.selected_keys(), vec![0, 1, 2, 3, 4]);
+
+  model.pick_color([0., 1., 1.]).unwrap();
+  model.set_color(0.6, [1., 1., 1., 1.]).unwrap();
+  assert_color(&model, 0.25, [0., 1., 1., 0.875]);
+  assert_eq!(model.active_channel(), red.unwrap());
+  assert_eq!(model.selected_keys(), vec![0, 1, 2, 4, 5]);
+}
+
+#[test]
+fn load_gradient_documents() {
+  let path = std::env::temp_dir().join("spline-editor-gradient.json");
+  let document = r#"{
+    "version": 5,
+    "mode": "gradient",
+    "channels": [{
+      "name": "green",
+      "color": [0.3, 0.8, 0.3],
+      "visible": true,
+      "locked": false,
+      "spline": [{ "t": 0.5, "value": [0.5, 0.25], "interpolation": "linear" }]
+    }]
+  }"#;
+  std::fs::write(&path, document).unwrap();
+
+  let mut model = EditorModel::new();
+  model.load(&path).unwrap();
+  assert_eq!(model.mode(), CurveMode::Gradient);
+  assert_color(&model, 0., [0., 0.25, 0., 1.]);
+
+  // the mode is written along with a version that knows it
+  model.save(&path).unwrap();
+  let saved = Document::load(&path).unwrap();
+  std::fs::remove_file(&path).unwrap();
+  assert_eq!(saved.version, DOCUMENT_VERSION);
+  assert_eq!(saved.mode, CurveMode::Gradient);
+}
+
+#[test]
+fn pick_palette_swatches() {
+  let palette = Palette::new(1.5);
+  let &(p, color) = palette.swatches().last().unwrap();
+
+  assert_eq!(palette.pick(p + ScreenPos::new(0.01, 0.)), Some(color));
+  assert_eq!(color, [1.; 3]);
+  assert_eq!(palette.pick(ScreenPos::new(0., 0.)), None);
+  assert!(palette
+    .swatches()
+    .iter()
+    .all(|&(p, _)| p.x > -1.5 && p.y < 1.));
+}
+
+#[test]
+fn bake_and_write_ramps() {
+  let model = red_to_blue();
+  let ramp = Ramp::bake(&model.gradient(), Sampling::Count(3)).unwrap();
+  assert_eq!(
+    ramp.texels,
+    vec![[255, 0, 0, 255], [128, 0, 128, 191], [0, 0, 255, 128]]
+  );
+
+  let mut out = Vec::new();
+  ramp.write(&mut out).unwrap();
+  assert_eq!(out.len(), 20 + 3 * 4);
+  assert_eq!(out[0..4], RAMP_MAGIC);
+  assert_eq!(out[8..12], 3u32.to_le_bytes());
+  assert_eq!(out[20..24], [255, 0, 0, 255]);
+}
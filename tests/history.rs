@@ -0,0 +1,72 @@
+use spline_editor::history::History;
+
+/// Record a transaction going from `before` to `after`.
+fn record(history: &mut History<u32>, before: u32, after: u32) {
+  history.begin(before);
+  history.commit(&after);
+}
+
+#[test]
+fn undo_depth_is_bounded() {
+  let mut history = History::new(3);
+
+  for state in 0..5 {
+    record(&mut history, state, state + 1);
+  }
+
+  // only the last three transactions can be undone
+  assert_eq!(history.undo(5), Some(4));
+  assert_eq!(history.undo(4), Some(3));
+  assert_eq!(history.undo(3), Some(2));
+  assert_eq!(history.undo(2), None);
+
+  // and redone
+  assert_eq!(history.redo(2), Some(3));
+  assert_eq!(history.redo(3), Some(4));
+  assert_eq!(history.redo(4), Some(5));
+  assert_eq!(history.redo(5), None);
+
+  // nothing can be undone without any depth
+  let mut history = History::new(0);
+  record(&mut history, 0, 1);
+  record(&mut history, 1, 2);
+  assert_eq!(history.undo(2), None);
+}
+
+#[test]
+fn commits_clear_the_redo_stack() {
+  let mut history = History::new(8);
+  record(&mut history, 0, 1);
+  record(&mut history, 1, 2);
+  assert_eq!(history.undo(2), Some(1));
+
+  record(&mut history, 1, 3);
+  assert_eq!(history.redo(3), None);
+  assert_eq!(history.undo(3), Some(1));
+  assert_eq!(history.undo(1), Some(0));
+}
+
+#[test]
+fn unchanged_states_are_not_recorded() {
+  let mut history = History::new(8);
+  record(&mut history, 0, 1);
+  assert_eq!(history.undo(1), Some(0));
+
+  // a transaction that changes nothing neither pushes a snapshot nor drops the redo stack
+  record(&mut history, 0, 0);
+  assert!(!history.is_recording());
+  assert_eq!(history.redo(0), Some(1));
+  assert_eq!(history.undo(1), Some(0));
+  assert_eq!(history.undo(0), None);
+}
+
+#[test]
+fn outermost_transactions_win() {
+  let mut history = History::new(8);
+  history.begin(0);
+  history.begin(1);
+  assert!(history.is_recording());
+  history.commit(&2);
+
+  assert_eq!(history.undo(2), Some(0));
+}
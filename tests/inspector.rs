@@ -0,0 +1,118 @@
+use spline_editor::{
+  camera::Camera,
+  inspector::{Field, Inspector, InspectorError},
+  model::{EditorModel, HandleSelection, ScreenPos},
+};
+use splines::Interpolation;
+
+fn type_text(inspector: &mut Inspector, text: &str) {
+  for c in text.chars() {
+    inspector.input(c);
+  }
+}
+
+#[test]
+fn type_time_and_value() {
+  let mut model = EditorModel::new();
+  model.add_point(ScreenPos::new(0.1, 0.), Interpolation::Linear);
+  model.add_point(ScreenPos::new(0.5, 0.5), Interpolation::Linear);
+  model.select(ScreenPos::new(0.1, 0.), &Camera::default());
+
+  let mut inspector = Inspector::new();
+  assert!(inspector.begin(&model));
+  assert_eq!(inspector.field(), Some(Field::Time));
+
+  // the key passes over its neighbour and is still the inspected one
+  type_text(&mut inspector, "0.75");
+  inspector.next_field(&mut model).unwrap();
+  assert_eq!(inspector.field(), Some(Field::Value));
+
+  type_text(&mut inspector, "1x.0");
+  inspector.commit(&mut model).unwrap();
+  assert!(!inspector.is_editing());
+
+  assert_eq!(Inspector::inspected_key(&model), Some(1));
+  let key = model.spline().keys()[1];
+  assert_eq!(key.t, 0.75);
+  assert_eq!(key.value, ScreenPos::new(0.75, 1.));
+
+  // each field is its own history entry
+  assert!(model.undo());
+  assert_eq!(model.spline().keys()[1].value, ScreenPos::new(0.75, 0.));
+}
+
+#[test]
+fn invalid_number_keeps_editing() {
+  let mut model = EditorModel::new();
+  model.add_point(ScreenPos::new(0.1, 0.), Interpolation::Linear);
+  model.select_all();
+
+  let mut inspector = Inspector::new();
+  inspector.begin(&model);
+  type_text(&mut inspector, "1-");
+
+  match inspector.commit(&mut model) {
+    Err(InspectorError::InvalidNumber(_)) => (),
+    r => panic!("unexpected result: {:?}", r),
+  }
+
+  assert!(inspector.is_editing());
+  assert_eq!(model.spline().keys()[0].t, 0.1);
+}
+
+#[test]
+fn infinite_numbers_are_invalid() {
+  let mut model = EditorModel::new();
+  model.add_point(ScreenPos::new(0.1, 0.), Interpolation::Linear);
+  model.select_all();
+
+  let mut inspector = Inspector::new();
+  inspector.begin(&model);
+
+  // too large for an f32
+  type_text(&mut inspector, "1e39");
+
+  match inspector.next_field(&mut model) {
+    Err(InspectorError::InvalidNumber(_)) => (),
+    r => panic!("unexpected result: {:?}", r),
+  }
+
+  assert!(inspector.is_editing());
+  assert_eq!(inspector.field(), Some(Field::Time));
+  assert_eq!(model.spline().keys()[0].t, 0.1);
+}
+
+#[test]
+fn type_handle_coordinates() {
+  let mut model = EditorModel::new();
+  model.add_point(
+    ScreenPos::new(0.5, 0.),
+    Interpolation::StrokeBezier(ScreenPos::new(0.4, 0.), ScreenPos::new(0.6, 0.)),
+  );
+  model.select_all();
+
+  let mut inspector = Inspector::new();
+  inspector.begin(&model);
+
+  // skip time and value, then type the output handle
+  for _ in 0..4 {
+    inspector.next_field(&mut model).unwrap();
+  }
+
+  assert_eq!(
+    inspector.field(),
+    Some(Field::HandleX(HandleSelection::Mirror))
+  );
+  type_text(&mut inspector, "0.7");
+  inspector.next_field(&mut model).unwrap();
+  type_text(&mut inspector, "-0.25");
+  inspector.commit(&mut model).unwrap();
+
+  assert_eq!(
+    model.spline().keys()[0].interpolation,
+    Interpolation::StrokeBezier(ScreenPos::new(0.4, 0.), ScreenPos::new(0.7, -0.25))
+  );
+
+  let desc = inspector.describe(&model).unwrap();
+  assert!(desc.contains("out: (0.700, -0.250)"), "{}", desc);
+}
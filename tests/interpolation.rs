@@ -0,0 +1,177 @@
+mod common;
+
+use common::{assert_same_curve, dense_samples};
+use spline_editor::{
+  interpolation::{convert, InterpolationKind},
+  mode::CurveMode,
+  model::ScreenPos,
+};
+use splines::{Interpolation, Key, Spline};
+
+#[test]
+fn kinds_cycle() {
+  let mut kinds = vec![InterpolationKind::Step];
+
+  while kinds.len() <= InterpolationKind::ALL.len() {
+    kinds.push(kinds[kinds.len() - 1].next());
+  }
+
+  // every mode is visited once before getting back to the first one
+  assert_eq!(kinds[..6], InterpolationKind::ALL);
+  assert_eq!(kinds[6], InterpolationKind::Step);
+  assert_eq!(
+    InterpolationKind::of(&Interpolation::StrokeBezier(
+      ScreenPos::new(0., 0.),
+      ScreenPos::new(0., 0.)
+    )),
+    Some(InterpolationKind::StrokeBezier)
+  );
+}
+
+#[test]
+fn straight_segments_stay_straight() {
+  let mut keys = vec![
+    Key::new(0., ScreenPos::new(0., 0.), Interpolation::Linear),
+    Key::new(1., ScreenPos::new(1., 2.), Interpolation::Linear),
+    Key::new(2., ScreenPos::new(2., 1.), Interpolation::Linear),
+  ];
+
+  // quadratic segment, then cubic one; the input handle of the first key doesn’t move
+  convert(&mut keys, CurveMode::Function, 0, InterpolationKind::Bezier);
+  convert(
+    &mut keys,
+    CurveMode::Function,
+    1,
+    InterpolationKind::StrokeBezier,
+  );
+  convert(
+    &mut keys,
+    CurveMode::Function,
+    2,
+    InterpolationKind::StrokeBezier,
+  );
+
+  assert_eq!(
+    keys[0].interpolation,
+    Interpolation::StrokeBezier(ScreenPos::new(-0.5, -1.), ScreenPos::new(1. / 3., 2. / 3.))
+  );
+
+  for p in dense_samples(&Spline::from_vec(keys.clone()))
+    .into_iter()
+    .flatten()
+  {
+    let expected = if p.x <= 1. { 2. * p.x } else { 3. - p.x };
+    assert!((p.y - expected).abs() < 1e-4, "{:?} is off the lines", p);
+  }
+}
+
+#[test]
+fn handles_are_kept() {
+  let stroke = Interpolation::StrokeBezier(ScreenPos::new(0.5, 1.), ScreenPos::new(1.2, 0.8));
+  let mut keys = vec![
+    Key::new(
+      0.,
+      ScreenPos::new(0., 0.),
+      Interpolation::Bezier(ScreenPos::new(0.3, 0.5)),
+    ),
+    Key::new(1., ScreenPos::new(1., 1.), stroke),
+    Key::new(2., ScreenPos::new(2., 0.), Interpolation::Linear),
+  ];
+  let before = dense_samples(&Spline::from_vec(keys.clone()));
+
+  // same mode: nothing changes
+  convert(
+    &mut keys,
+    CurveMode::Function,
+    1,
+    InterpolationKind::StrokeBezier,
+  );
+  assert_eq!(keys[1].interpolation, stroke);
+
+  // Bézier to stroke Bézier is exact
+  convert(
+    &mut keys,
+    CurveMode::Function,
+    0,
+    InterpolationKind::StrokeBezier,
+  );
+  assert_eq!(
+    keys[0].interpolation,
+    Interpolation::StrokeBezier(ScreenPos::new(-0.3, -0.5), ScreenPos::new(0.3, 0.5))
+  );
+  assert_same_curve(&before, &dense_samples(&Spline::from_vec(keys.clone())));
+
+  // and the output handle is kept the other way around
+  convert(&mut keys, CurveMode::Function, 1, InterpolationKind::Bezier);
+  assert_eq!(
+    keys[1].interpolation,
+    Interpolation::Bezier(ScreenPos::new(1.2, 0.8))
+  );
+}
+
+#[test]
+fn previous_segment_is_degree_elevated() {
+  let mut keys = vec![
+    Key::new(
+      0.,
+      ScreenPos::new(0., 0.),
+      Interpolation::Bezier(ScreenPos::new(0.2, 1.)),
+    ),
+    Key::new(1., ScreenPos::new(1., 0.), Interpolation::Cosine),
+    Key::new(2., ScreenPos::new(2., 1.), Interpolation::Linear),
+  ];
+  let before = dense_samples(&Spline::from_vec(keys.clone()));
+
+  // the first segment was quadratic and becomes cubic without changing
+  convert(
+    &mut keys,
+    CurveMode::Function,
+    1,
+    InterpolationKind::StrokeBezier,
+  );
+  assert!(matches!(
+    keys[0].interpolation,
+    Interpolation::StrokeBezier(..)
+  ));
+
+  let after = dense_samples(&Spline::from_vec(keys.clone()));
+  assert_same_curve(&before[..100], &after[..100]);
+
+  // the cosine segment starts flat, and so does its Bézier replacement
+  match keys[1].interpolation {
+    Interpolation::StrokeBezier(_, output) => assert!((output.y - 0.).abs() < 1e-3, "{:?}", output),
+    ref interpolation => panic!("unexpected interpolation: {:?}", interpolation),
+  }
+}
+
+#[test]
+fn convert_keys_sharing_a_time() {
+  for &mode in &[CurveMode::Function, CurveMode::Path] {
+    let mut keys = vec![
+      Key::new(0., ScreenPos::new(0., 0.), Interpolation::Linear),
+      Key::new(1., ScreenPos::new(1., 0.), Interpolation::Linear),
+      Key::new(1., ScreenPos::new(1., 1.), Interpolation::Linear),
+      Key::new(2., ScreenPos::new(2., 1.), Interpolation::Linear),
+    ];
+
+    for i in 0..keys.len() {
+      convert(&mut keys, mode, i, InterpolationKind::StrokeBezier);
+    }
+
+    for key in &keys {
+      match key.interpolation {
+        Interpolation::StrokeBezier(a, b) => {
+          assert!(a.x.is_finite() && a.y.is_finite(), "{:?}", key);
+          assert!(b.x.is_finite() && b.y.is_finite(), "{:?}", key);
+        }
+        ref interpolation => panic!("not a stroke Bézier key: {:?}", interpolation),
+      }
+    }
+
+    let spline = Spline::from_vec(keys);
+    assert!(dense_samples(&spline)
+      .iter()
+      .flatten()
+      .all(|p| p.x.is_finite() && p.y.is_finite()));
+  }
+}
@@ -0,0 +1,685 @@
+mod common;
+
+use cgmath::InnerSpace as _;
+use common::{assert_same_curve, dense_samples, graph_samples};
+use spline_editor::{
+  camera::Camera,
+  interpolation::InterpolationKind,
+  mode::CurveMode,
+  model::{EditorModel, HandleSelection, ScreenPos, Selection},
+  tangent::TangentMode,
+};
+use splines::Interpolation;
+
+fn model_with_keys(points: &[[f32; 2]]) -> EditorModel {
+  let mut model = EditorModel::new();
+
+  for &[x, y] in points {
+    model.add_point(ScreenPos::new(x, y), Interpolation::Linear);
+  }
+
+  model
+}
+
+#[test]
+fn add_points_sorted_by_time() {
+  let model = model_with_keys(&[[0.5, 0.], [0.1, 0.2], [0.9, -0.5]]);
+  let ts: Vec<_> = model.spline().keys().iter().map(|k| k.t).collect();
+
+  assert_eq!(ts, vec![0.1, 0.5, 0.9]);
+}
+
+#[test]
+fn select_nearest_key() {
+  let mut model = model_with_keys(&[[0.1, 0.], [0.5, 0.]]);
+  let camera = Camera::default();
+
+  assert!(matches!(
+    model.select(ScreenPos::new(0.49, 0.01), &camera),
+    Some(Selection::Key(1))
+  ));
+  assert_eq!(model.selected_keys(), vec![1]);
+
+  assert!(model.select(ScreenPos::new(0.3, 0.5), &camera).is_none());
+  assert!(!model.is_selecting());
+}
+
+#[test]
+fn move_key_updates_time_and_value() {
+  let mut model = model_with_keys(&[[0.1, 0.], [0.5, 0.]]);
+
+  model.move_key(1, ScreenPos::new(0.6, 0.3)).unwrap();
+
+  let key = model.spline().keys()[1];
+  assert_eq!(key.t, 0.6);
+  assert_eq!(key.value, ScreenPos::new(0.6, 0.3));
+}
+
+#[test]
+fn toggle_interpolation_cycles() {
+  let mut model = model_with_keys(&[[0.1, 0.]]);
+  model.select_all();
+
+  model.toggle_interpolation();
+  assert_eq!(
+    model.spline().keys()[0].interpolation,
+    Interpolation::Cosine
+  );
+
+  model.toggle_interpolation();
+  assert!(matches!(
+    model.spline().keys()[0].interpolation,
+    Interpolation::Bezier(_)
+  ));
+
+  model
+    .move_handle(0, ScreenPos::new(0.5, 0.3), HandleSelection::Mirror)
+    .unwrap();
+  assert_eq!(
+    model.spline().keys()[0].interpolation,
+    Interpolation::Bezier(ScreenPos::new(-0.3, -0.3))
+  );
+
+  model.toggle_interpolation();
+  assert!(matches!(
+    model.spline().keys()[0].interpolation,
+    Interpolation::StrokeBezier(..)
+  ));
+
+  model.toggle_interpolation();
+  assert_eq!(
+    model.spline().keys()[0].interpolation,
+    Interpolation::CatmullRom
+  );
+
+  model.toggle_interpolation();
+  assert_eq!(
+    model.spline().keys()[0].interpolation,
+    Interpolation::Step(0.5)
+  );
+}
+
+#[test]
+fn undo_redo_coalesced_drag() {
+  let mut model = model_with_keys(&[[0.1, 0.]]);
+
+  model.begin_transaction();
+  model.move_key(0, ScreenPos::new(0.2, 0.1)).unwrap();
+  model.move_key(0, ScreenPos::new(0.3, 0.2)).unwrap();
+  model.commit_transaction();
+
+  assert!(model.undo());
+  assert_eq!(model.spline().keys()[0].value, ScreenPos::new(0.1, 0.));
+
+  assert!(model.redo());
+  assert_eq!(model.spline().keys()[0].value, ScreenPos::new(0.3, 0.2));
+
+  // undo the drag and the creation of the key
+  assert!(model.undo());
+  assert!(model.undo());
+  assert!(model.spline().is_empty());
+  assert!(!model.undo());
+}
+
+#[test]
+fn revision_tracks_changes() {
+  let mut model = model_with_keys(&[[0.1, 0.]]);
+  let revision = model.revision();
+
+  model.deselect();
+  assert_ne!(model.revision(), revision);
+}
+
+#[test]
+fn shift_click_toggles_selection() {
+  let mut model = model_with_keys(&[[0.1, 0.], [0.5, 0.], [0.9, 0.]]);
+  let camera = Camera::default();
+
+  model.select(ScreenPos::new(0.1, 0.), &camera);
+  model.toggle_select(ScreenPos::new(0.9, 0.), &camera);
+  assert_eq!(model.selected_keys(), vec![0, 2]);
+
+  model.toggle_select(ScreenPos::new(0.1, 0.), &camera);
+  assert_eq!(model.selected_keys(), vec![2]);
+}
+
+#[test]
+fn marquee_selection() {
+  let mut model = model_with_keys(&[[0.1, 0.], [0.5, 0.5], [0.6, -0.2], [0.9, 0.]]);
+
+  model.begin_marquee(ScreenPos::new(0.7, 0.6));
+  model.update_marquee(ScreenPos::new(0.4, -0.3));
+  assert!(model.marquee().is_some());
+
+  model.end_marquee(false);
+  assert!(model.marquee().is_none());
+  assert_eq!(model.selected_keys(), vec![1, 2]);
+
+  // additive marquee
+  model.begin_marquee(ScreenPos::new(0., -1.));
+  model.update_marquee(ScreenPos::new(0.2, 1.));
+  model.end_marquee(true);
+  assert_eq!(model.selected_keys(), vec![0, 1, 2]);
+}
+
+#[test]
+fn group_drag_keeps_offsets() {
+  let mut model = model_with_keys(&[[0.1, 0.], [0.3, 0.2], [0.9, 0.]]);
+  model.select_in_rect(ScreenPos::new(0., -1.), ScreenPos::new(0.5, 1.), false);
+
+  model.begin_drag(Selection::Key(1), ScreenPos::new(0.3, 0.2));
+  model.drag_to(ScreenPos::new(0.4, 0.1)).unwrap();
+  model.drag_to(ScreenPos::new(0.5, 0.3)).unwrap();
+  model.end_drag();
+
+  let values: Vec<_> = model.spline().keys().iter().map(|k| k.value).collect();
+  assert!((values[0] - ScreenPos::new(0.3, 0.1)).magnitude() < 1e-6);
+  assert!((values[1] - ScreenPos::new(0.5, 0.3)).magnitude() < 1e-6);
+  assert_eq!(model.selected_keys(), vec![0, 1]);
+
+  // the whole drag is undone at once
+  assert!(model.undo());
+  assert_eq!(model.spline().keys()[1].value, ScreenPos::new(0.3, 0.2));
+}
+
+#[test]
+fn remove_selected_keys() {
+  let mut model = model_with_keys(&[[0.1, 0.], [0.3, 0.2], [0.9, 0.]]);
+  model.select_all();
+  let camera = Camera::default();
+  model.toggle_select(ScreenPos::new(0.3, 0.2), &camera);
+
+  let removed = model.remove_selected();
+  assert_eq!(removed.len(), 2);
+  assert_eq!(model.spline().len(), 1);
+  assert_eq!(model.spline().keys()[0].t, 0.3);
+  assert!(!model.is_selecting());
+}
+
+#[test]
+fn insert_key_in_bezier_segments() {
+  let mut model = EditorModel::new();
+  model.add_point(
+    ScreenPos::new(0., 0.),
+    Interpolation::Bezier(ScreenPos::new(0.2, 0.5)),
+  );
+  model.add_point(
+    ScreenPos::new(0.5, 1.),
+    Interpolation::Bezier(ScreenPos::new(0.6, 1.2)),
+  );
+  model.add_point(
+    ScreenPos::new(1., -1.),
+    Interpolation::StrokeBezier(ScreenPos::new(0.8, -0.2), ScreenPos::new(1.1, -1.)),
+  );
+  model.add_point(ScreenPos::new(1.5, 0.), Interpolation::Linear);
+  model.add_point(ScreenPos::new(2., 0.), Interpolation::Linear);
+
+  let before = dense_samples(model.spline());
+
+  // cubic segments, between Bézier and stroke Bézier keys
+  assert_eq!(model.insert_key(0.3).unwrap(), 1);
+  assert_eq!(model.insert_key(0.6).unwrap(), 3);
+  // quadratic segment, ending on a linear key
+  assert_eq!(model.insert_key(1.2).unwrap(), 5);
+
+  assert_eq!(model.spline().len(), 8);
+  assert_eq!(model.selected_keys(), vec![5]);
+  assert_same_curve(&before, &dense_samples(model.spline()));
+
+  // each insertion is a single history entry
+  assert!(model.undo());
+  assert_eq!(model.spline().len(), 7);
+}
+
+#[test]
+fn insert_key_in_step_and_linear_segments() {
+  let mut model = EditorModel::new();
+  model.add_point(ScreenPos::new(0., 0.), Interpolation::Step(0.25));
+  model.add_point(ScreenPos::new(1., 1.), Interpolation::Step(0.75));
+  model.add_point(ScreenPos::new(2., 0.), Interpolation::Linear);
+  model.add_point(ScreenPos::new(3., 1.), Interpolation::Linear);
+
+  let before = graph_samples(model.spline());
+
+  // before and after the jump
+  model.insert_key(0.5).unwrap();
+  model.insert_key(1.25).unwrap();
+  model.insert_key(2.5).unwrap();
+
+  assert_eq!(model.spline().len(), 7);
+  assert_same_curve(&before, &graph_samples(model.spline()));
+
+  // there’s nothing to split outside of the curve or on an existing key
+  assert!(model.insert_key(-1.).is_err());
+  assert!(model.insert_key(2.).is_err());
+}
+
+#[test]
+fn insert_key_in_path_segments() {
+  let mut model = EditorModel::new();
+  model.set_mode(CurveMode::Path);
+  model.add_point(ScreenPos::new(0., 0.), Interpolation::Step(0.25));
+  model.add_point(
+    ScreenPos::new(0., 2.),
+    Interpolation::Bezier(ScreenPos::new(1., 3.)),
+  );
+  model.add_point(
+    ScreenPos::new(2., 2.),
+    Interpolation::StrokeBezier(ScreenPos::new(2., 3.), ScreenPos::new(2., 1.)),
+  );
+  model.add_point(ScreenPos::new(1., 0.), Interpolation::Linear);
+  model.add_point(ScreenPos::new(1., -1.), Interpolation::Linear);
+
+  let ts: Vec<_> = model.spline().keys().iter().map(|k| k.t).collect();
+  let before = dense_samples(model.spline());
+
+  // keys are inserted where the path goes, whatever their time
+  for w in ts.windows(2) {
+    model.insert_key((w[0] + w[1]) * 0.5).unwrap();
+  }
+
+  assert_eq!(model.spline().len(), 9);
+  assert_same_curve(&before, &dense_samples(model.spline()));
+}
+
+#[test]
+fn drag_step_threshold() {
+  let mut model = EditorModel::new();
+  model.add_point(ScreenPos::new(0., 0.), Interpolation::Step(0.5));
+  model.add_point(ScreenPos::new(2., 1.), Interpolation::Linear);
+  let camera = Camera::default();
+
+  // the marker sits on the jump
+  assert_eq!(model.threshold_marker(0), Some(ScreenPos::new(1., 0.5)));
+  assert_eq!(model.threshold_marker(1), None);
+
+  let grabbed = model.select(ScreenPos::new(1.01, 0.5), &camera).unwrap();
+  assert_eq!(grabbed, Selection::Threshold(0));
+
+  model.begin_drag(grabbed, ScreenPos::new(1., 0.5));
+  model.drag_to(ScreenPos::new(1.5, 0.3)).unwrap();
+  model.drag_to(ScreenPos::new(3., 0.3)).unwrap();
+  model.end_drag();
+
+  // clamped to the segment
+  assert_eq!(
+    model.spline().keys()[0].interpolation,
+    Interpolation::Step(1.)
+  );
+
+  assert!(model.undo());
+  assert_eq!(
+    model.spline().keys()[0].interpolation,
+    Interpolation::Step(0.5)
+  );
+
+  // switching away from step drops the marker from the selection
+  model.select(ScreenPos::new(1., 0.5), &camera);
+  model.toggle_select(ScreenPos::new(0., 0.), &camera);
+  model.set_interpolation(InterpolationKind::Linear);
+  assert_eq!(model.selection(), &[Selection::Key(0)]);
+  assert!(model.set_threshold(0, 0.25).is_err());
+}
+
+#[test]
+fn step_thresholds_of_coincident_keys() {
+  let mut model = EditorModel::new();
+  model.add_point(ScreenPos::new(1., 0.), Interpolation::Step(0.5));
+  model.add_point(ScreenPos::new(1., 1.), Interpolation::Linear);
+
+  model.move_threshold(0, ScreenPos::new(1.5, 0.5)).unwrap();
+  model.begin_drag(Selection::Threshold(0), ScreenPos::new(1., 0.5));
+  model.drag_to(ScreenPos::new(0.5, 0.5)).unwrap();
+  model.end_drag();
+
+  assert_eq!(
+    model.spline().keys()[0].interpolation,
+    Interpolation::Step(0.5)
+  );
+}
+
+/// Path with one key of each handle-bearing interpolation and a linear one, all selected.
+///
+/// Handles are free 2D points in paths; function curves pin them to their time.
+fn model_with_handles() -> EditorModel {
+  let mut model = EditorModel::new();
+  model.set_mode(CurveMode::Path);
+  model.add_point(
+    ScreenPos::new(0., 0.),
+    Interpolation::Bezier(ScreenPos::new(0.1, 0.2)),
+  );
+  model.add_point(
+    ScreenPos::new(1., 1.),
+    Interpolation::StrokeBezier(ScreenPos::new(0.8, 0.9), ScreenPos::new(1.3, 1.2)),
+  );
+  model.add_point(ScreenPos::new(2., 0.), Interpolation::Linear);
+  model.select_all();
+  model
+}
+
+fn drag_all_by(model: &mut EditorModel, delta: ScreenPos) {
+  model.begin_drag(Selection::Key(0), ScreenPos::new(0., 0.));
+  model.drag_to(delta).unwrap();
+  model.end_drag();
+}
+
+#[test]
+fn handles_follow_dragged_keys() {
+  let mut model = model_with_handles();
+  drag_all_by(&mut model, ScreenPos::new(0.5, 0.5));
+
+  let keys = model.spline().keys();
+  assert_eq!(
+    keys[0].interpolation,
+    Interpolation::Bezier(ScreenPos::new(0.6, 0.7))
+  );
+  assert_eq!(
+    keys[1].interpolation,
+    Interpolation::StrokeBezier(ScreenPos::new(1.3, 1.4), ScreenPos::new(1.8, 1.7))
+  );
+  assert_eq!(keys[2].interpolation, Interpolation::Linear);
+  assert_eq!(keys[2].value, ScreenPos::new(2.5, 0.5));
+
+  // the same goes for single keys
+  model.move_key(1, ScreenPos::new(1.5, 1.)).unwrap();
+  assert_eq!(
+    model.spline().keys()[1].interpolation,
+    Interpolation::StrokeBezier(ScreenPos::new(1.3, 0.9), ScreenPos::new(1.8, 1.2))
+  );
+}
+
+#[test]
+fn handles_stay_while_keeping_handles() {
+  let mut model = model_with_handles();
+  model.set_keep_handles(true);
+  drag_all_by(&mut model, ScreenPos::new(0.5, 0.5));
+
+  let keys = model.spline().keys();
+  assert_eq!(keys[0].value, ScreenPos::new(0.5, 0.5));
+  assert_eq!(
+    keys[0].interpolation,
+    Interpolation::Bezier(ScreenPos::new(0.1, 0.2))
+  );
+  assert_eq!(keys[1].value, ScreenPos::new(1.5, 1.5));
+  assert_eq!(
+    keys[1].interpolation,
+    Interpolation::StrokeBezier(ScreenPos::new(0.8, 0.9), ScreenPos::new(1.3, 1.2))
+  );
+  assert_eq!(keys[2].value, ScreenPos::new(2.5, 0.5));
+}
+
+#[test]
+fn drag_key_past_another() {
+  let mut model = model_with_keys(&[[0.1, 0.], [0.5, 0.5], [0.9, 0.]]);
+  let camera = Camera::default();
+  let dragged = model.key_id(0).unwrap();
+  let other = model.key_id(1).unwrap();
+
+  let grabbed = model.select(ScreenPos::new(0.1, 0.), &camera).unwrap();
+  model.begin_drag(grabbed, ScreenPos::new(0.1, 0.));
+  model.drag_to(ScreenPos::new(0.6, 0.)).unwrap();
+
+  // the dragged key is now the second one, and is still the one following the cursor
+  assert_eq!(model.key_index(dragged), Some(1));
+  assert_eq!(model.key_index(other), Some(0));
+  assert_eq!(model.selected_keys(), vec![1]);
+
+  model.drag_to(ScreenPos::new(0.7, 0.2)).unwrap();
+  model.end_drag();
+
+  let keys = model.spline().keys();
+  assert_eq!(keys[0].value, ScreenPos::new(0.5, 0.5));
+  assert_eq!(keys[1].value, ScreenPos::new(0.7, 0.2));
+
+  // undoing keeps the selection on the same key
+  assert!(model.undo());
+  assert_eq!(model.key_index(dragged), Some(0));
+  assert_eq!(model.selected_keys(), vec![0]);
+  assert_eq!(model.spline().keys()[0].value, ScreenPos::new(0.1, 0.));
+}
+
+#[test]
+fn channels_are_edited_separately() {
+  let mut model = model_with_keys(&[[0.1, 0.], [0.5, 0.5]]);
+  let camera = Camera::default();
+
+  assert_eq!(model.add_channel("rotation"), 1);
+  assert_eq!(model.active_channel(), 1);
+  assert!(model.spline().is_empty());
+
+  // keys of other channels cannot be picked
+  assert_eq!(model.select(ScreenPos::new(0.1, 0.), &camera), None);
+
+  model.add_point(ScreenPos::new(0.3, 1.), Interpolation::Linear);
+  assert_eq!(model.spline().len(), 1);
+  assert_eq!(model.channels()[0].spline().len(), 2);
+
+  // switching channels clears the selection
+  model.select_all();
+  model.set_active_channel(0).unwrap();
+  assert!(!model.is_selecting());
+  assert_eq!(model.spline().len(), 2);
+
+  // undo goes back to the channel that was edited
+  assert!(model.undo());
+  assert_eq!(model.active_channel(), 1);
+  assert!(model.spline().is_empty());
+
+  assert!(model.undo());
+  assert_eq!(model.channels().len(), 1);
+  assert_eq!(model.active_channel(), 0);
+}
+
+#[test]
+fn locked_and_hidden_channels_are_read_only() {
+  let mut model = model_with_keys(&[[0.1, 0.], [0.5, 0.5]]);
+  let camera = Camera::default();
+
+  model.set_channel_locked(0, true).unwrap();
+  model.add_point(ScreenPos::new(0.9, 0.), Interpolation::Linear);
+  assert_eq!(model.spline().len(), 2);
+
+  // locked keys can still be picked and inspected, but not moved
+  let grabbed = model.select(ScreenPos::new(0.1, 0.), &camera).unwrap();
+  model.begin_drag(grabbed, ScreenPos::new(0.1, 0.));
+  assert!(!model.is_dragging());
+  assert!(model.move_key(0, ScreenPos::new(0.2, 0.)).is_err());
+  assert!(model.remove_selected().is_empty());
+
+  // hidden keys cannot even be picked
+  model.set_channel_locked(0, false).unwrap();
+  model.set_channel_visible(0, false).unwrap();
+  assert!(!model.is_selecting());
+  assert_eq!(model.select(ScreenPos::new(0.1, 0.), &camera), None);
+  assert!(model.insert_key(0.3).is_err());
+
+  model.set_channel_visible(0, true).unwrap();
+  assert!(model.insert_key(0.3).is_ok());
+}
+
+#[test]
+fn remove_channels() {
+  let mut model = model_with_keys(&[[0.1, 0.]]);
+  model.add_channel("b");
+  model.add_channel("c");
+  model.set_active_channel(1).unwrap();
+
+  // the next channel becomes active
+  assert_eq!(model.remove_channel(1).unwrap().name(), "b");
+  assert_eq!(model.active_channel(), 1);
+  assert_eq!(model.channels()[1].name(), "c");
+
+  // there’s always a channel left
+  model.remove_channel(1).unwrap();
+  model.remove_channel(0).unwrap();
+  assert_eq!(model.channels().len(), 1);
+  assert!(model.spline().is_empty());
+  assert!(model.remove_channel(1).is_err());
+}
+
+#[test]
+fn save_and_load_channels() {
+  let path = std::env::temp_dir().join("spline-editor-channels.json");
+  let mut model = model_with_keys(&[[0.1, 0.], [0.5, 0.5]]);
+  model.add_channel("intensity");
+  model.add_point(ScreenPos::new(0.2, 1.), Interpolation::Linear);
+  model.set_channel_color(1, [1., 0., 0.]).unwrap();
+  model.set_channel_locked(1, true).unwrap();
+  model.save(&path).unwrap();
+
+  let mut loaded = EditorModel::new();
+  loaded.load(&path).unwrap();
+  std::fs::remove_file(&path).unwrap();
+
+  let channels = loaded.channels();
+  assert_eq!(channels.len(), 2);
+  assert_eq!(channels[0].spline().len(), 2);
+  assert_eq!(channels[1].name(), "intensity");
+  assert_eq!(channels[1].color(), [1., 0., 0.]);
+  assert!(channels[1].is_locked());
+  assert_eq!(
+    channels[1].spline().keys()[0].value,
+    ScreenPos::new(0.2, 1.)
+  );
+}
+
+#[test]
+fn save_and_load_all_interpolations() {
+  let path = std::env::temp_dir().join("spline-editor-interpolations.json");
+  let mut model = EditorModel::new();
+  model.set_mode(CurveMode::Path);
+
+  for &(p, interpolation) in &[
+    ([0., 0.], Interpolation::Step(0.25)),
+    ([1., 0.5], Interpolation::Linear),
+    ([2., 0.], Interpolation::Cosine),
+    ([3., 1.], Interpolation::Bezier(ScreenPos::new(3.5, 2.))),
+    (
+      [4., 0.],
+      Interpolation::StrokeBezier(ScreenPos::new(4.5, 1.), ScreenPos::new(4.8, -0.5)),
+    ),
+    ([5., 1.], Interpolation::CatmullRom),
+    ([6., 0.], Interpolation::Linear),
+    ([7., 1.], Interpolation::Linear),
+  ] {
+    model.add_point(ScreenPos::new(p[0], p[1]), interpolation);
+  }
+  model.save(&path).unwrap();
+
+  let mut loaded = EditorModel::new();
+  loaded.load(&path).unwrap();
+  std::fs::remove_file(&path).unwrap();
+
+  let kinds: Vec<_> = loaded
+    .spline()
+    .keys()
+    .iter()
+    .filter_map(|key| InterpolationKind::of(&key.interpolation))
+    .collect();
+  assert_eq!(&kinds[..6], &InterpolationKind::ALL[..]);
+
+  assert_eq!(loaded.mode(), CurveMode::Path);
+  assert_eq!(loaded.spline().keys(), model.spline().keys());
+  assert_eq!(
+    dense_samples(loaded.spline()),
+    dense_samples(model.spline())
+  );
+}
+
+#[test]
+fn load_single_spline_documents() {
+  let path = std::env::temp_dir().join("spline-editor-legacy.json");
+  let legacy = r#"{
+    "version": 2,
+    "spline": [{ "t": 0.1, "value": [0.1, 0.5], "interpolation": "linear" }],
+    "tangents": ["aligned"]
+  }"#;
+  std::fs::write(&path, legacy).unwrap();
+
+  let mut model = EditorModel::new();
+  model.load(&path).unwrap();
+  std::fs::remove_file(&path).unwrap();
+
+  assert_eq!(model.channels().len(), 1);
+  assert_eq!(model.spline().keys()[0].value, ScreenPos::new(0.1, 0.5));
+  assert_eq!(model.tangent_mode(0), Some(TangentMode::Aligned));
+}
+
+fn path_with_points(points: &[[f32; 2]]) -> EditorModel {
+  let mut model = EditorModel::new();
+  model.set_mode(CurveMode::Path);
+
+  for &[x, y] in points {
+    model.add_point(ScreenPos::new(x, y), Interpolation::Linear);
+  }
+
+  model
+}
+
+#[test]
+fn path_points_are_appended() {
+  let model = path_with_points(&[[0., 0.], [3., 4.], [0., 4.]]);
+  let keys = model.spline().keys();
+  let ts: Vec<_> = keys.iter().map(|k| k.t).collect();
+
+  // going back left doesn’t go back in time
+  assert_eq!(ts, vec![0., 5., 8.]);
+  assert_eq!(keys[2].value, ScreenPos::new(0., 4.));
+}
+
+#[test]
+fn path_keys_keep_their_time() {
+  let mut model = path_with_points(&[[0., 0.], [1., 0.], [2., 0.]]);
+
+  model.move_key(1, ScreenPos::new(5., 2.)).unwrap();
+  let key = model.spline().keys()[1];
+  assert_eq!(key.t, 1.);
+  assert_eq!(key.value, ScreenPos::new(5., 2.));
+
+  // retiming past a neighbour reorders the keys
+  model.set_key_time(1, 3.).unwrap();
+  let ts: Vec<_> = model.spline().keys().iter().map(|k| k.t).collect();
+  assert_eq!(ts, vec![0., 2., 3.]);
+  assert_eq!(model.spline().keys()[2].value, ScreenPos::new(5., 2.));
+
+  model.undo();
+  assert_eq!(model.spline().keys()[1].t, 1.);
+}
+
+#[test]
+fn time_paths_by_arc_length() {
+  let mut model = path_with_points(&[[0., 0.], [1., 0.], [4., 0.]]);
+  model.set_key_time(1, 3.).unwrap();
+  model.set_key_time(2, 8.).unwrap();
+
+  model.time_by_arc_length().unwrap();
+
+  let ts: Vec<_> = model.spline().keys().iter().map(|k| k.t).collect();
+  assert_eq!(ts[0], 0.);
+  assert!((ts[1] - 2.).abs() < 1e-4);
+  assert_eq!(ts[2], 8.);
+}
+
+#[test]
+fn path_time_at_cursor() {
+  let model = path_with_points(&[[0., 0.], [0., 1.]]);
+  let t = model
+    .time_at(ScreenPos::new(0.2, 0.5), &Camera::default())
+    .unwrap();
+
+  // the path goes up; the horizontal coordinate of the cursor is irrelevant
+  assert!((t - 0.5).abs() < 0.05);
+}
+
+#[test]
+fn function_mode_puts_keys_at_their_time() {
+  let mut model = path_with_points(&[[0., 0.], [0., 1.]]);
+  model.set_mode(CurveMode::Function);
+
+  let keys = model.spline().keys();
+  assert_eq!(keys[1].value, ScreenPos::new(1., 1.));
+
+  model.undo();
+  assert_eq!(model.mode(), CurveMode::Path);
+  assert_eq!(model.spline().keys()[1].value, ScreenPos::new(0., 1.));
+}
@@ -0,0 +1,151 @@
+mod common;
+
+use common::{assert_close, assert_same_function, dense_samples};
+use spline_editor::{
+  camera::Camera,
+  model::{EditorModel, HandleSelection, ScreenPos, Selection},
+  scalar::{from_scalar, handle_offsets, pin_handles, to_scalar},
+};
+use splines::{Interpolation, Key, Spline};
+
+/// Handles of a key, the input one first.
+fn handles(key: &Key<f32, ScreenPos>) -> Vec<ScreenPos> {
+  match key.interpolation {
+    Interpolation::Bezier(u) => vec![u],
+    Interpolation::StrokeBezier(input, output) => vec![input, output],
+    _ => Vec::new(),
+  }
+}
+
+fn scalar_spline() -> Spline<f32, f32> {
+  Spline::from_vec(vec![
+    Key::new(0., 0., Interpolation::Bezier(0.5)),
+    Key::new(1., 1., Interpolation::StrokeBezier(1.5, 0.8)),
+    Key::new(3., 0., Interpolation::Bezier(-0.5)),
+    Key::new(4., 0.5, Interpolation::Linear),
+    Key::new(5., 0., Interpolation::Linear),
+  ])
+}
+
+#[test]
+fn convert_without_loss() {
+  let spline = scalar_spline();
+  let graph = from_scalar(&spline);
+  let keys = graph.keys();
+
+  // handles are at their time in the graph: a third of the way in cubic segments, half of the
+  // way in quadratic ones
+  assert_eq!(keys[1].value, ScreenPos::new(1., 1.));
+  let h = handles(&keys[1]);
+  assert_close(h[0], ScreenPos::new(2. / 3., 1.5));
+  assert_close(h[1], ScreenPos::new(5. / 3., 0.8));
+  assert_close(handles(&keys[2])[0], ScreenPos::new(3.5, -0.5));
+
+  assert_eq!(to_scalar(&graph).keys(), spline.keys());
+
+  for i in 0..=100 {
+    let t = 5. * i as f32 / 100.;
+    assert_eq!(
+      graph.clamped_sample(t).map(|p| p.y),
+      spline.clamped_sample(t)
+    );
+  }
+}
+
+#[test]
+fn pinned_handles_keep_their_slope() {
+  let mut keys = from_scalar(&scalar_spline()).keys().to_vec();
+
+  // stretch the second segment, carrying the handle of its last key
+  keys[2].t = 4.;
+  keys[2].value.x = 4.;
+  keys[2].interpolation = Interpolation::Bezier(ScreenPos::new(4.5, -0.5));
+  keys[3].t = 4.5;
+  keys[3].value.x = 4.5;
+  assert!(pin_handles(&mut keys));
+
+  let (input, output) = handle_offsets(&keys, 1).unwrap();
+  assert!((input - 1. / 3.).abs() < 1e-6 && (output - 1.).abs() < 1e-6);
+  let h = handles(&keys[1]);
+  assert_close(h[0], ScreenPos::new(2. / 3., 1.5));
+  assert_close(h[1], ScreenPos::new(2., 0.7));
+  assert_close(handles(&keys[2])[0], ScreenPos::new(4.25, -0.25));
+
+  // pinning again doesn’t change anything
+  assert!(!pin_handles(&mut keys));
+}
+
+#[test]
+fn handles_stay_on_their_tangent() {
+  let mut model = EditorModel::new();
+  model.import_scalar("scalar", &scalar_spline());
+  assert_eq!(model.channels().len(), 2);
+  assert_eq!(model.active_channel(), 1);
+
+  // dragging the output handle far away only changes its slope
+  model
+    .set_handle(1, ScreenPos::new(3., 0.), HandleSelection::Mirror)
+    .unwrap();
+  let keys = model.spline().keys();
+  assert_close(handles(&keys[1])[1], ScreenPos::new(5. / 3., 2. / 3.));
+
+  // moving a key keeps the slope of the handles of its neighbours
+  model.move_key(3, ScreenPos::new(3.5, 1.)).unwrap();
+  let keys = model.spline().keys();
+  assert!(keys.iter().all(|key| key.value.x == key.t));
+  assert_close(handles(&keys[2])[0], ScreenPos::new(3.25, -0.25));
+
+  // handles left behind keep their value instead
+  model.set_keep_handles(true);
+  model.select(ScreenPos::new(3., 0.), &Camera::default());
+  model.begin_drag(Selection::Key(2), ScreenPos::new(3., 0.));
+  model.drag_to(ScreenPos::new(2.5, 0.)).unwrap();
+  model.end_drag();
+  let keys = model.spline().keys();
+  assert_close(handles(&keys[2])[0], ScreenPos::new(3., -0.25));
+}
+
+#[test]
+fn load_places_function_handles() {
+  let path = std::env::temp_dir().join("spline-editor-placed-handles.json");
+  let spline = Spline::from_vec(vec![
+    Key::new(
+      0.,
+      ScreenPos::new(0., 0.),
+      Interpolation::Bezier(ScreenPos::new(5., 1.)),
+    ),
+    Key::new(
+      1.,
+      ScreenPos::new(1., 1.),
+      Interpolation::StrokeBezier(ScreenPos::new(-2., 0.5), ScreenPos::new(0., 2.)),
+    ),
+    Key::new(2., ScreenPos::new(2., 0.), Interpolation::Linear),
+  ]);
+  let document = format!(
+    r#"{{ "version": 6, "mode": "function", "channels": [{{
+      "name": "curve", "color": [1, 1, 1], "visible": true, "locked": false, "spline": {}
+    }}] }}"#,
+    serde_json::to_string(&spline).unwrap()
+  );
+  std::fs::write(&path, document).unwrap();
+
+  // handles placed elsewhere move to their time, without changing the shape of the curve
+  let mut model = EditorModel::new();
+  model.load(&path).unwrap();
+  assert_same_function(&dense_samples(&spline), &dense_samples(model.spline()));
+  let placed = handles(&model.spline().keys()[1]);
+  assert_close(placed[0], ScreenPos::new(2. / 3., 0.5));
+  assert_close(placed[1], ScreenPos::new(1.5, 2.));
+
+  // from then on, saving and loading changes nothing
+  model.save(&path).unwrap();
+  let saved = std::fs::read_to_string(&path).unwrap();
+  let mut loaded = EditorModel::new();
+  loaded.load(&path).unwrap();
+  loaded.save(&path).unwrap();
+  let resaved = std::fs::read_to_string(&path).unwrap();
+  std::fs::remove_file(&path).unwrap();
+
+  assert_eq!(saved, resaved);
+  assert_eq!(loaded.spline().keys(), model.spline().keys());
+}
@@ -0,0 +1,89 @@
+use spline_editor::slots::{Slot, SlotBuffer};
+
+#[test]
+fn only_changes_are_dirty() {
+  let mut buffer = SlotBuffer::new(0);
+  assert!(buffer.take_grown());
+
+  buffer.set(0, &[1, 2, 3]);
+  buffer.set(1, &[4, 5]);
+  assert_eq!(buffer.take_dirty(), vec![0..3, 4..6]);
+
+  // same content
+  buffer.set(0, &[1, 2, 3]);
+  assert!(buffer.take_dirty().is_empty());
+
+  // shrinking pads with the fill value
+  buffer.set(0, &[1, 7]);
+  assert_eq!(buffer.take_dirty(), vec![0..3]);
+  assert_eq!(buffer.data()[..6], [1, 7, 0, 0, 4, 5]);
+  assert!(!buffer.take_grown());
+}
+
+#[test]
+fn slots_move_when_full() {
+  let mut buffer = SlotBuffer::new(0);
+  buffer.set(0, &[1; 4]);
+  buffer.set(1, &[2; 4]);
+  buffer.take_dirty();
+
+  // item 0 doesn’t fit its slot anymore and moves after item 1
+  let slot = buffer.set(0, &[3; 5]);
+  assert_eq!(
+    slot,
+    Slot {
+      start: 8,
+      len: 5,
+      capacity: 8
+    }
+  );
+  assert_eq!(buffer.used(), 16);
+  assert_eq!(buffer.take_dirty(), vec![0..4, 8..13]);
+  assert_eq!(buffer.data()[..4], [0; 4]);
+
+  // the buffer grows when needed
+  buffer.set(2, &[4; 100]);
+  assert!(buffer.take_grown());
+  assert_eq!(buffer.data().len(), 256);
+}
+
+#[test]
+fn truncate_and_compact() {
+  let mut buffer = SlotBuffer::new(0);
+
+  for i in 0..16 {
+    buffer.set(i, &[i; 4]);
+  }
+
+  // move the first items away, leaving holes, then drop the other ones
+  for i in 0..4 {
+    buffer.set(i, &[i; 5]);
+  }
+
+  buffer.truncate(4);
+  assert_eq!(buffer.len(), 4);
+  assert_eq!(buffer.used(), 96);
+
+  assert!(buffer.compact());
+  assert_eq!(buffer.used(), 32);
+  assert_eq!(buffer.slot(1).map(|slot| slot.start), Some(8));
+  assert_eq!(buffer.data()[8..13], [1; 5]);
+  assert!(!buffer.compact());
+}
+
+#[test]
+fn clear_frees_slot() {
+  let mut buffer = SlotBuffer::new(0);
+  buffer.set(0, &[1, 2]);
+  buffer.set(1, &[3, 4]);
+  buffer.take_dirty();
+
+  buffer.clear(0);
+  assert_eq!(buffer.slot(0), None);
+  assert_eq!(buffer.take_dirty(), vec![0..2]);
+  assert_eq!(buffer.data()[..6], [0, 0, 0, 0, 3, 4]);
+
+  // clearing an item without slot does nothing
+  buffer.clear(5);
+  assert!(buffer.take_dirty().is_empty());
+}
@@ -0,0 +1,57 @@
+use spline_editor::{
+  grid::{Grid, LineKind},
+  model::{EditorModel, ScreenPos},
+  snap::SnapTarget,
+};
+use splines::Interpolation;
+
+#[test]
+fn grid_lines_budget() {
+  let grid = Grid::default();
+
+  // a small area shows minor lines and both axes
+  let lines = grid.lines(ScreenPos::new(-0.05, -0.1), ScreenPos::new(0.3, 0.6), 100);
+  assert!(lines.iter().any(|l| l.kind == LineKind::Minor));
+  assert_eq!(lines.iter().filter(|l| l.kind == LineKind::Axis).count(), 2);
+
+  // a huge area drops minor lines and stays within budget
+  let lines = grid.lines(
+    ScreenPos::new(-1000., -1000.),
+    ScreenPos::new(1000., 1000.),
+    100,
+  );
+  assert!(lines.iter().all(|l| l.kind != LineKind::Minor));
+  assert!(lines.len() <= 2 * 101);
+}
+
+#[test]
+fn snap_to_grid_and_frames() {
+  let mut model = EditorModel::new();
+  model.snapping_mut().enabled = true;
+
+  model.add_point(ScreenPos::new(0.505, 0.26), Interpolation::Linear);
+  let key = model.spline().keys()[0];
+  assert!((key.t - 0.5).abs() < 1e-5 && (key.value.y - 0.25).abs() < 1e-5);
+
+  model.snapping_mut().target = SnapTarget::Frames;
+  model.move_key(0, ScreenPos::new(1.01, 0.33)).unwrap();
+  let key = model.spline().keys()[0];
+  assert!((key.t - 1.).abs() < 1e-5);
+  assert_eq!(key.value.y, 0.33);
+}
+
+#[test]
+fn snap_to_other_keys() {
+  let mut model = EditorModel::new();
+  model.add_point(ScreenPos::new(0.2, 0.5), Interpolation::Linear);
+  model.add_point(ScreenPos::new(0.8, -0.5), Interpolation::Linear);
+
+  model.snapping_mut().enabled = true;
+  model.snapping_mut().target = SnapTarget::Keys;
+
+  // close to the first key’s value, far from any key’s time
+  model.move_key(1, ScreenPos::new(0.6, 0.51)).unwrap();
+  let key = model.spline().keys()[1];
+  assert_eq!(key.t, 0.6);
+  assert_eq!(key.value.y, 0.5);
+}
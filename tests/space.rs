@@ -0,0 +1,282 @@
+mod common;
+
+use cgmath::Vector3;
+use common::assert_close;
+use spline_editor::{
+  camera::Camera,
+  document::{Document, DOCUMENT_VERSION},
+  export::ExportError,
+  mode::CurveMode,
+  model::{EditorError, EditorModel, ScreenPos, Selection},
+  space::{Orbit, Projection},
+};
+use splines::{Interpolation, Key, Spline};
+
+fn camera_path() -> Spline<f32, Vector3<f32>> {
+  Spline::from_vec(vec![
+    Key::new(0., Vector3::new(0., 1., 0.), Interpolation::Linear),
+    Key::new(
+      1.,
+      Vector3::new(2., 1., -1.),
+      Interpolation::StrokeBezier(Vector3::new(1., 1., -1.), Vector3::new(3., 2., -1.)),
+    ),
+    Key::new(3., Vector3::new(4., 0., 2.), Interpolation::Linear),
+  ])
+}
+
+fn model_with_path() -> EditorModel {
+  let mut model = EditorModel::new();
+  model.import_space(&camera_path()).unwrap();
+  model
+}
+
+#[test]
+fn space_mode_adds_component_channels() {
+  let mut model = EditorModel::new();
+  model.set_mode(CurveMode::Space);
+
+  let names: Vec<_> = model.channels().iter().map(|c| c.name()).collect();
+  assert_eq!(names, vec!["curve", "x", "y", "z"]);
+  assert_eq!(model.active_channel(), 1);
+}
+
+#[test]
+fn convert_vector3_splines_without_loss() {
+  let model = model_with_path();
+  assert_eq!(model.mode(), CurveMode::Space);
+
+  let spline = model.space().to_spline().unwrap();
+  assert_eq!(spline.keys(), camera_path().keys());
+  assert_close(
+    model.space().position(2.).unwrap(),
+    camera_path().sample(2.).unwrap(),
+  );
+
+  // a key missing in one component cannot be converted
+  let mut model = model;
+  model.set_active_channel(2).unwrap();
+  model.add_point(ScreenPos::new(4., 0.), Interpolation::Linear);
+  assert!(matches!(
+    model.space().to_spline(),
+    Err(ExportError::UnalignedComponents(t)) if t == 4.
+  ));
+}
+
+#[test]
+fn load_space_documents() {
+  let path = std::env::temp_dir().join("spline-editor-space.json");
+  let channel = |name: &str, y: f32| {
+    format!(
+      r#"{{
+        "name": "{}",
+        "color": [1, 1, 1],
+        "visible": true,
+        "locked": false,
+        "spline": [
+          {{ "t": 0, "value": [0, 0], "interpolation": "linear" }},
+          {{ "t": 1, "value": [1, {}], "interpolation": "linear" }}
+        ]
+      }}"#,
+      name, y
+    )
+  };
+  let document = format!(
+    r#"{{ "version": 6, "mode": "space", "channels": [{}, {}, {}] }}"#,
+    channel("x", 1.),
+    channel("y", 2.),
+    channel("z", 3.)
+  );
+  std::fs::write(&path, document).unwrap();
+
+  let mut model = EditorModel::new();
+  model.load(&path).unwrap();
+  assert_eq!(model.mode(), CurveMode::Space);
+  assert_close(
+    model.space().position(0.5).unwrap(),
+    Vector3::new(0.5, 1., 1.5),
+  );
+
+  // the mode is written along with a version that knows it
+  model.save(&path).unwrap();
+  let saved = Document::load(&path).unwrap();
+  std::fs::remove_file(&path).unwrap();
+  assert_eq!(saved.version, DOCUMENT_VERSION);
+  assert_eq!(saved.mode, CurveMode::Space);
+}
+
+#[test]
+fn orthographic_projections() {
+  let p = Vector3::new(1., 2., 3.);
+
+  assert_eq!(Projection::Top.project(p), Some(ScreenPos::new(1., -3.)));
+  assert_eq!(Projection::Front.project(p), Some(ScreenPos::new(1., 2.)));
+  assert_eq!(Projection::Side.project(p), Some(ScreenPos::new(-3., 2.)));
+
+  // the hidden coordinate is kept
+  for &projection in &[Projection::Top, Projection::Front, Projection::Side] {
+    let q = Vector3::new(5., 6., 7.);
+    let moved = projection.unproject(projection.project(q).unwrap(), p);
+    assert_eq!(projection.project(moved.unwrap()), projection.project(q));
+  }
+
+  assert_eq!(
+    Projection::Top.unproject(ScreenPos::new(5., 6.), p),
+    Some(Vector3::new(5., 2., -6.))
+  );
+}
+
+#[test]
+fn select_and_move_keys_in_projection() {
+  let mut model = model_with_path();
+  let camera = Camera::default();
+  model.set_projection(Some(Projection::Top));
+
+  // the key at t = 1 is at (2, 1) from the top
+  assert_eq!(model.projected_key(1), Some(ScreenPos::new(2., 1.)));
+  assert_eq!(
+    model.select(ScreenPos::new(2.01, 1.), &camera),
+    Some(Selection::Key(1))
+  );
+
+  // moving it in the plane keeps its height and time
+  model
+    .move_projected_keys(&[(1, ScreenPos::new(1.5, 2.))])
+    .unwrap();
+  assert_close(
+    model.space().position(1.).unwrap(),
+    Vector3::new(1.5, 1., -2.),
+  );
+  assert_eq!(model.selected_keys(), vec![1]);
+  assert_eq!(model.space().key_times(), vec![0., 1., 3.]);
+
+  // other views move other coordinates, each move being a single history entry
+  model.set_projection(Some(Projection::Front));
+  model
+    .move_projected_keys(&[(1, ScreenPos::new(1.5, 3.))])
+    .unwrap();
+  assert_close(
+    model.space().position(1.).unwrap(),
+    Vector3::new(1.5, 3., -2.),
+  );
+  assert!(model.undo());
+  assert_close(
+    model.space().position(1.).unwrap(),
+    Vector3::new(1.5, 1., -2.),
+  );
+
+  // points are appended at the distance from the last key
+  model.add_point(ScreenPos::new(4., 4.), Interpolation::Linear);
+  assert_eq!(model.space().key_times(), vec![0., 1., 3., 7.]);
+  assert_close(
+    model.space().position(7.).unwrap(),
+    Vector3::new(4., 4., 2.),
+  );
+}
+
+#[test]
+fn edit_channel_values_in_projection() {
+  let mut model = model_with_path();
+  model.set_projection(Some(Projection::Top));
+
+  // times and values are the ones of the active channel, x, whatever the view
+  model.set_key_time(1, 1.5).unwrap();
+  assert_eq!(model.channels()[1].spline().keys()[1].t, 1.5);
+  assert_eq!(model.channels()[1].spline().keys()[1].value.y, 2.);
+  assert_eq!(model.channels()[2].spline().keys()[1].t, 1.);
+
+  model.move_keys(&[(0, ScreenPos::new(0., -1.))]).unwrap();
+  assert_eq!(
+    model.channels()[1].spline().keys()[0].value,
+    ScreenPos::new(0., -1.)
+  );
+  assert_eq!(model.channels()[3].spline().keys()[0].value.y, 0.);
+
+  // moving keys in the plane of a view needs one
+  model.set_projection(None);
+  assert!(matches!(
+    model.move_projected_keys(&[(0, ScreenPos::new(0., 0.))]),
+    Err(EditorError::ProjectionNotEditable)
+  ));
+}
+
+#[test]
+fn rejected_positions_change_nothing() {
+  let mut model = model_with_path();
+  model.set_position(2., Vector3::new(1., 1., 1.)).unwrap();
+
+  // x could be set, but y cannot
+  assert!(matches!(
+    model.set_position(0.5, Vector3::new(5., f32::INFINITY, 0.)),
+    Err(EditorError::InvalidValue(_))
+  ));
+  assert_eq!(model.space().key_times(), vec![0., 1., 2., 3.]);
+  assert_close(
+    model.space().position(0.).unwrap(),
+    Vector3::new(0., 1., 0.),
+  );
+
+  // nothing was recorded either: undoing goes back before the last position
+  assert!(model.undo());
+  assert_eq!(model.space().key_times(), vec![0., 1., 3.]);
+}
+
+#[test]
+fn drag_keys_of_components_with_more_keys() {
+  let mut model = model_with_path();
+
+  // x gets more keys than the other components
+  model.add_point(ScreenPos::new(0.5, 0.5), Interpolation::Linear);
+  model.add_point(ScreenPos::new(2., 3.), Interpolation::Linear);
+  model.select_all();
+  assert_eq!(model.selected_keys(), vec![0, 1, 2, 3, 4]);
+
+  model.set_projection(Some(Projection::Top));
+  let grabbed = model.projected_key(2).unwrap();
+  model.begin_drag(Selection::Key(2), grabbed);
+  model.drag_to(grabbed + ScreenPos::new(0.5, 0.)).unwrap();
+  model.drag_to(grabbed + ScreenPos::new(1., 0.)).unwrap();
+  model.end_drag();
+
+  assert_close(
+    model.space().position(1.).unwrap(),
+    Vector3::new(3., 1., -1.),
+  );
+  assert_close(
+    model.space().position(0.).unwrap(),
+    Vector3::new(1., 1., 0.),
+  );
+  assert_eq!(model.space().key_times(), vec![0., 0.5, 1., 2., 3.]);
+  assert_eq!(model.selected_keys(), vec![0, 1, 2, 3, 4]);
+
+  // the whole drag is a single history entry
+  assert!(model.undo());
+  assert_close(
+    model.space().position(1.).unwrap(),
+    Vector3::new(2., 1., -1.),
+  );
+}
+
+#[test]
+fn orbit_previews_cannot_be_edited() {
+  let mut model = model_with_path();
+  let orbit = Orbit::around(&model.space());
+  model.set_projection(Some(Projection::Orbit(orbit)));
+
+  assert!(model.projected_key(0).is_some());
+  assert!(matches!(
+    model.move_projected_keys(&[(0, ScreenPos::new(0., 0.))]),
+    Err(EditorError::ProjectionNotEditable)
+  ));
+
+  // the pivot is seen at its own position, whatever the angle
+  let mut turned = orbit;
+  turned.rotate(ScreenPos::new(0.3, -0.2));
+  assert_eq!(
+    Projection::Orbit(turned).project(orbit.pivot),
+    Some(ScreenPos::new(0., 0.))
+  );
+
+  // projections only apply to 3D paths
+  model.set_mode(CurveMode::Function);
+  assert_eq!(model.projection(), None);
+}
@@ -0,0 +1,163 @@
+mod common;
+
+use common::assert_close;
+use spline_editor::{
+  mode::CurveMode,
+  model::{EditorModel, HandleSelection, ScreenPos},
+  tangent::{AutoTangent, TangentMode},
+};
+use splines::Interpolation;
+
+fn handles(model: &EditorModel, i: usize) -> (ScreenPos, ScreenPos) {
+  match model.spline().keys()[i].interpolation {
+    Interpolation::StrokeBezier(input, output) => (input, output),
+    ref interpolation => panic!("not a stroke Bézier key: {:?}", interpolation),
+  }
+}
+
+fn stroke_model(mode: CurveMode) -> EditorModel {
+  let mut model = EditorModel::new();
+  model.set_mode(mode);
+  model.add_point(ScreenPos::new(0., 0.), Interpolation::Linear);
+  model.add_point(
+    ScreenPos::new(1., 1.),
+    Interpolation::StrokeBezier(ScreenPos::new(0.5, 1.), ScreenPos::new(1.2, 0.5)),
+  );
+  model.add_point(ScreenPos::new(2., 0.), Interpolation::Linear);
+  model
+}
+
+#[test]
+fn mirrored_and_aligned_handles() {
+  // handles are free in paths; function curves pin them to their time
+  let mut model = stroke_model(CurveMode::Path);
+  assert_eq!(model.tangent_mode(1), Some(TangentMode::Free));
+
+  model.select_all();
+  model.set_tangent_mode(TangentMode::Mirrored);
+
+  // the output handle wins
+  assert_close(handles(&model, 1).0, ScreenPos::new(0.8, 1.5));
+
+  model
+    .set_handle(1, ScreenPos::new(0.7, 1.), HandleSelection::Own)
+    .unwrap();
+  assert_close(handles(&model, 1).1, ScreenPos::new(1.3, 1.));
+
+  // aligned handles keep their own lengths
+  model.set_tangent_mode(TangentMode::Aligned);
+  model
+    .set_handle(1, ScreenPos::new(1., 1.6), HandleSelection::Mirror)
+    .unwrap();
+  let (input, output) = handles(&model, 1);
+  assert_close(output, ScreenPos::new(1., 1.6));
+  assert_close(input, ScreenPos::new(1., 0.7));
+
+  // free handles are independent
+  model.set_tangent_mode(TangentMode::Free);
+  model
+    .set_handle(1, ScreenPos::new(0.5, 0.5), HandleSelection::Own)
+    .unwrap();
+  assert_close(handles(&model, 1).1, ScreenPos::new(1., 1.6));
+
+  // modes are part of the history
+  assert!(model.undo());
+  assert!(model.undo());
+  assert_eq!(model.tangent_mode(1), Some(TangentMode::Aligned));
+}
+
+#[test]
+fn auto_handles_follow_neighbours() {
+  let mut model = stroke_model(CurveMode::Function);
+  model.select_all();
+  model.set_tangent_mode(TangentMode::Auto);
+
+  // flat between two keys at the same value; the next segment is quadratic
+  let (input, output) = handles(&model, 1);
+  assert_close(input, ScreenPos::new(2. / 3., 1.));
+  assert_close(output, ScreenPos::new(1.5, 1.));
+
+  // moving a neighbour updates the handles
+  model.move_keys(&[(2, ScreenPos::new(2., 2.))]).unwrap();
+  let (input, output) = handles(&model, 1);
+  assert_close(input, ScreenPos::new(2. / 3., 2. / 3.));
+  assert_close(output, ScreenPos::new(1.5, 1.5));
+
+  // and moving a handle by hand makes them aligned, the other one keeping its time
+  model
+    .set_handle(1, ScreenPos::new(1.5, 1.), HandleSelection::Mirror)
+    .unwrap();
+  assert_eq!(model.tangent_mode(1), Some(TangentMode::Aligned));
+  assert_close(handles(&model, 1).0, ScreenPos::new(2. / 3., 1.));
+}
+
+/// Model with keys at the given values, evenly spaced in time, all selected.
+fn model_with_values(values: &[f32]) -> EditorModel {
+  let mut model = EditorModel::new();
+
+  for (i, &y) in values.iter().enumerate() {
+    model.add_point(ScreenPos::new(i as f32, y), Interpolation::Linear);
+  }
+
+  model.select_all();
+  model
+}
+
+/// Check that no segment goes past the values of its keys.
+fn assert_no_overshoot(model: &EditorModel) {
+  let keys = model.spline().keys();
+
+  for w in keys.windows(2) {
+    let (min, max) = (
+      w[0].value.y.min(w[1].value.y),
+      w[0].value.y.max(w[1].value.y),
+    );
+
+    for j in 0..=100 {
+      let t = w[0].t + (w[1].t - w[0].t) * j as f32 / 100.;
+      let y = model.spline().clamped_sample(t).unwrap().y;
+      assert!(
+        y >= min - 1e-4 && y <= max + 1e-4,
+        "{} overshoots at t = {}",
+        y,
+        t
+      );
+    }
+  }
+}
+
+#[test]
+fn smooth_tangents_overshoot() {
+  let mut model = model_with_values(&[0., 0., 1., 1., 0.2]);
+  model.compute_tangents(AutoTangent::Smooth);
+
+  // Catmull-Rom slope, a third of the segments away
+  assert_close(
+    handles(&model, 2).1,
+    ScreenPos::new(2. + 1. / 3., 1. + 1. / 6.),
+  );
+  assert_eq!(model.tangent_mode(2), Some(TangentMode::Aligned));
+
+  let y = model.spline().clamped_sample(2.5).unwrap().y;
+  assert!(y > 1., "{}", y);
+}
+
+#[test]
+fn clamped_and_monotone_tangents_never_overshoot() {
+  for &scheme in &[AutoTangent::Clamped, AutoTangent::Monotone] {
+    let mut model = model_with_values(&[0., 0., 1., 1., 0.2, 3., 2.9]);
+    model.compute_tangents(scheme);
+    assert_no_overshoot(&model);
+
+    // flat on plateaus and extrema
+    assert_close(handles(&model, 1).1, ScreenPos::new(1. + 1. / 3., 0.));
+    assert_close(handles(&model, 4).0, ScreenPos::new(4. - 1. / 3., 0.2));
+  }
+
+  // only part of the keys, the other segments being quadratic
+  let mut model = model_with_values(&[0., 0.1, 2., 2.1]);
+  model.deselect();
+  model.select_in_rect(ScreenPos::new(0.5, -1.), ScreenPos::new(1.5, 1.), false);
+  model.compute_tangents(AutoTangent::Monotone);
+  assert_no_overshoot(&model);
+}